@@ -1,4 +1,21 @@
+use crate::attributes::{self, CardAttribute, ListingFormat};
+use crate::cache::EnrichmentCache;
+use crate::captcha::{self, ActiveChallenge, CaptchaDetected, CaptchaGate};
+use crate::checkpoint::{Checkpoint, CheckpointStage};
+use crate::cli::ScrapeOptions;
+use crate::condition::Condition;
+use crate::coverage::{CoverageDrop, CoverageReport, SelectorHits};
+use crate::diff::ListingDiff;
 use crate::event::{AppEvent, Event, EventHandler};
+use crate::export;
+use crate::history::PriceHistory;
+use crate::item_url::ItemUrl;
+use crate::marketplace::Marketplace;
+use crate::pipeline::{Pipeline, Stage};
+use crate::pool::{self, SessionPool};
+use crate::rate_limit::RateLimiter;
+use crate::selectors::SelectorRegistry;
+use crate::store::{RunSummary, Store};
 use fantoccini::{Client, ClientBuilder};
 use log::{error, info};
 use ratatui::{
@@ -7,8 +24,15 @@ use ratatui::{
 };
 use scraper::{Html, Selector};
 use serde::{Deserialize, Serialize};
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+use std::path::{Path, PathBuf};
+use std::io::Write;
 use std::process::{Child, Command};
-use chrono::Utc;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Utc};
+use color_eyre::eyre::eyre;
 
 #[derive(Debug, Default, Clone)]
 pub struct ScrollState {
@@ -41,8 +65,31 @@ impl ScrollState {
     }
 }
 
+/// Seller stats read from the store page. A stat is `None` when its element was missing or
+/// could not be read.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SellerStats {
+    /// Number of items the seller has sold
+    pub items_sold: Option<u32>,
+    /// Feedback score text, e.g. `99.5% positive feedback`
+    pub feedback_score: Option<String>,
+    /// Number of followers of the store
+    pub follower_count: Option<u32>,
+}
+
+impl SellerStats {
+    /// Returns how many of the three stats were read.
+    pub fn found(&self) -> usize {
+        [self.items_sold.is_some(), self.feedback_score.is_some(), self.follower_count.is_some()]
+            .into_iter()
+            .filter(|found| *found)
+            .count()
+    }
+}
+
 /// Represents an eBay listing with all relevant information for CSV export.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Listing {
     /// The title of the listing
     pub title: String,
@@ -54,6 +101,10 @@ pub struct Listing {
     pub condition: Option<String>,
     /// Number of watchers for this item
     pub watchers: Option<u32>,
+    /// Number of bids, for auctions
+    pub bids: Option<u32>,
+    /// Whether the card is a sponsored or promoted placement
+    pub sponsored: bool,
     /// Seller username
     pub seller: Option<String>,
     /// Seller feedback score
@@ -62,6 +113,8 @@ pub struct Listing {
     pub buy_it_now: bool,
     /// Whether the item accepts "Best Offer"
     pub accepts_offers: bool,
+    /// How the item is sold: by auction or at a fixed Buy It Now price
+    pub format: Option<ListingFormat>,
     /// Item location
     pub location: Option<String>,
     /// Number of items available
@@ -72,6 +125,8 @@ pub struct Listing {
     pub item_id: Option<String>,
     /// Item URL for direct access
     pub url: Option<String>,
+    /// Selected variation of a multi-variation listing
+    pub variation_id: Option<String>,
     /// Any additional notes or features
     pub notes: Vec<String>,
     /// Item specifics (brand, model, color, etc.) as key-value pairs
@@ -88,15 +143,19 @@ impl Default for Listing {
             shipping: None,
             condition: None,
             watchers: None,
+            bids: None,
+            sponsored: false,
             seller: None,
             seller_feedback: None,
             buy_it_now: false,
             accepts_offers: false,
+            format: None,
             location: None,
             quantity_available: None,
             is_new_listing: false,
             item_id: None,
             url: None,
+            variation_id: None,
             notes: Vec::new(),
             item_specifics: Vec::new(),
             description: None,
@@ -104,6 +163,58 @@ impl Default for Listing {
     }
 }
 
+/// Item ID of eBay's "Shop on eBay" placeholder card.
+const PLACEHOLDER_ITEM_ID: &str = "123456";
+
+impl Listing {
+    /// Returns the numeric value of the card price, if it can be parsed.
+    pub fn price_value(&self) -> Option<f64> {
+        parse_price(&self.price)
+    }
+
+    /// Returns the standard condition the card's condition text maps to, if it names one.
+    pub fn condition_category(&self) -> Option<Condition> {
+        self.condition.as_deref().and_then(Condition::parse)
+    }
+
+    /// Returns the link of the item page: the canonical URL of the card, or one on `marketplace`
+    /// built from the item ID for listings that only have an ID.
+    pub fn item_url(&self, marketplace: Marketplace) -> Option<String> {
+        self.url
+            .clone()
+            .or_else(|| self.item_id.as_deref().map(|item_id| marketplace.item_url(item_id)))
+    }
+
+    /// Returns true for the "Shop on eBay" cards eBay puts in front of the real results. They
+    /// have a title and price but no real item behind them.
+    pub fn is_placeholder(&self) -> bool {
+        self.title.trim().eq_ignore_ascii_case("shop on ebay") || self.item_id.as_deref() == Some(PLACEHOLDER_ITEM_ID)
+    }
+}
+
+/// Parses the first amount in a price text such as "$1,234.56", "EUR 1.234,56" or
+/// "$10.00 to $20.00". A separator followed by one or two digits at the end is the decimal point.
+pub fn parse_price(text: &str) -> Option<f64> {
+    let start = text.find(|c: char| c.is_ascii_digit())?;
+    let amount: String = text[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.' || *c == ',')
+        .collect();
+    let amount = amount.trim_end_matches(['.', ',']);
+    let decimal = amount
+        .rfind(['.', ','])
+        .filter(|&index| (1..=2).contains(&(amount.len() - index - 1)));
+    let normalized: String = amount
+        .char_indices()
+        .filter_map(|(index, c)| match c {
+            '.' | ',' if Some(index) == decimal => Some('.'),
+            '.' | ',' => None,
+            c => Some(c),
+        })
+        .collect();
+    normalized.parse().ok()
+}
+
 /// Application state representing different phases of the eBay scraping process.
 #[derive(Debug, Default, PartialEq, Eq)]
 pub enum AppState {
@@ -112,6 +223,8 @@ pub enum AppState {
     Loading,
     /// The application is running.
     Running,
+    /// The application shows the changes between two scrape results.
+    Diff,
 }
 
 /// Represents the current view mode of the scrollview widget.
@@ -124,6 +237,9 @@ pub enum ScrollViewMode {
     Table,
 }
 
+/// How long quitting waits for background tasks to wind down before aborting them.
+const TASK_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Main application structure managing the eBay scraper state and WebDriver interactions.
 #[derive(Debug)]
 pub struct App {
@@ -137,8 +253,14 @@ pub struct App {
     pub client: Option<Client>,
     /// The geckodriver process.
     pub geckodriver: Option<Child>,
-    /// Progress value from 0.0 to 1.0 representing scraping completion.
-    pub progress: f64,
+    /// Status, result and timing of every stage of the scrape run.
+    pub pipeline: Pipeline,
+    /// Cancels every background task of the run when quitting.
+    pub cancel: CancellationToken,
+    /// Cancels the background task of the current stage only.
+    pub stage_cancel: CancellationToken,
+    /// Background tasks that drive the browser, awaited when quitting.
+    pub tasks: JoinSet<()>,
     /// Current status message displayed to user.
     pub progress_message: String,
     /// eBay seller's feedback score (e.g., "99.1% positive").
@@ -147,10 +269,12 @@ pub struct App {
     pub items_sold: Option<u32>,
     /// Number of followers for the eBay seller.
     pub follower_count: Option<u32>,
-    /// Whether a CAPTCHA challenge is currently active.
-    pub captcha_detected: bool,
+    /// The CAPTCHA challenge currently waiting for the user, if any.
+    pub captcha: Option<ActiveChallenge>,
     /// Whether the app is waiting for user interaction (e.g., solving CAPTCHA).
     pub waiting_for_user_input: bool,
+    /// Gate that pauses every scraping step while a challenge is being solved.
+    pub captcha_gate: Arc<CaptchaGate>,
     /// Scraped eBay listings.
     pub listings: Vec<Listing>,
     /// Selected listing index for table navigation
@@ -165,22 +289,54 @@ pub struct App {
     pub scroll_view_state: ScrollState,
     /// Whether the user has locked to a specific section (true = locked)
     pub section_locked: bool,
+    /// Options for the current scrape run.
+    pub options: ScrapeOptions,
+    /// Shared limiter that every page navigation waits on.
+    pub rate_limiter: Arc<RateLimiter>,
+    /// CSS selectors of listing cards and item pages.
+    pub selectors: Arc<SelectorRegistry>,
+    /// Latest status line of each enrichment worker, indexed by worker.
+    pub worker_status: Vec<String>,
+    /// Checkpoint of the scrape in progress, if one has been written or resumed.
+    pub checkpoint: Option<Checkpoint>,
+    /// Previously enriched item details, used to skip unchanged items.
+    pub enrichment_cache: EnrichmentCache,
+    /// Which fallback selectors supplied the card fields of this run's listings.
+    pub selector_hits: SelectorHits,
+    /// Per-field coverage of the finished run.
+    pub coverage: Option<CoverageReport>,
+    /// Fields whose coverage dropped sharply since the previous run.
+    pub coverage_drops: Vec<CoverageDrop>,
+    /// When the current run started.
+    pub started_at: DateTime<Utc>,
+    /// Changes compared to the previous run, if known.
+    pub diff: Option<ListingDiff>,
+    /// Price, watcher and quantity history of the seller's items across recorded runs.
+    pub price_history: PriceHistory,
+    /// Exported file being viewed instead of a live scrape, if any.
+    pub viewed_file: Option<PathBuf>,
 }
 
 impl Default for App {
     fn default() -> Self {
+        let options = ScrapeOptions::default();
+        let events = EventHandler::new();
         Self {
             running: true,
             state: AppState::default(),
-            events: EventHandler::new(),
+            captcha_gate: Arc::new(CaptchaGate::new(events.sender.clone(), options.captcha_timeout())),
+            events,
             client: None,
             geckodriver: None,
-            progress: 0.0,
+            pipeline: Pipeline::default(),
+            cancel: CancellationToken::new(),
+            stage_cancel: CancellationToken::new(),
+            tasks: JoinSet::new(),
             progress_message: String::new(),
             feedback_score: None,
             items_sold: None,
             follower_count: None,
-            captcha_detected: false,
+            captcha: None,
             waiting_for_user_input: false,
             listings: Vec::new(),
             selected_listing_index: 0,
@@ -189,19 +345,142 @@ impl Default for App {
             paragraph_scroll_offset: 0,
             scroll_view_state: ScrollState::default(),
             section_locked: false,
+            rate_limiter: Arc::new(RateLimiter::new(options.rate_limit_config())),
+            selectors: Arc::new(SelectorRegistry::default()),
+            options,
+            worker_status: Vec::new(),
+            checkpoint: None,
+            enrichment_cache: EnrichmentCache::default(),
+            selector_hits: SelectorHits::default(),
+            coverage: None,
+            coverage_drops: Vec::new(),
+            started_at: Utc::now(),
+            diff: None,
+            price_history: PriceHistory::default(),
+            viewed_file: None,
         }
     }
 }
 
 impl App {
-    /// Constructs a new instance of [`App`].
-    pub fn new() -> Self {
-        Self::default()
+    /// Constructs a new instance of [`App`] for the given run options.
+    pub fn new(options: ScrapeOptions) -> Self {
+        let checkpoint = if options.resume {
+            Self::load_checkpoint(&options.seller)
+        } else {
+            None
+        };
+        let enrichment_cache = EnrichmentCache::load(&EnrichmentCache::path()).unwrap_or_else(|e| {
+            error!("Failed to load enrichment cache, starting empty: {}", e);
+            EnrichmentCache::default()
+        });
+        let price_history = Self::load_price_history(&options);
+        let selectors = SelectorRegistry::load(&options.selectors).unwrap_or_else(|e| {
+            error!("Failed to load selectors, using the built-in ones: {}", e);
+            SelectorRegistry::default()
+        });
+        let mut app = Self {
+            rate_limiter: Arc::new(RateLimiter::new(options.rate_limit_config())),
+            selectors: Arc::new(selectors),
+            checkpoint,
+            enrichment_cache,
+            price_history,
+            ..Self::default()
+        };
+        app.captcha_gate = Arc::new(CaptchaGate::new(app.events.sender.clone(), options.captcha_timeout()));
+        app.options = options;
+        app
+    }
+
+    /// Grabs the user's attention for a challenge: rings the terminal bell and runs the
+    /// configured notification command.
+    fn alert_challenge(&self, challenge: &CaptchaDetected) {
+        let mut stdout = std::io::stdout();
+        if let Err(e) = stdout.write_all(b"\x07").and_then(|_| stdout.flush()) {
+            error!("Failed to ring the terminal bell: {}", e);
+        }
+
+        if let Some(command) = &self.options.notify_command {
+            let spawned = Command::new("sh")
+                .arg("-c")
+                .arg(command)
+                .env("EBAY_CHALLENGE", challenge.kind.to_string())
+                .env("EBAY_CHALLENGE_URL", &challenge.url)
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .spawn();
+            if let Err(e) = spawned {
+                error!("Failed to run notify command: {}", e);
+            }
+        }
+    }
+
+    /// Loads the price history of the seller from the database, if one has been written yet.
+    fn load_price_history(options: &ScrapeOptions) -> PriceHistory {
+        if !options.database.exists() {
+            return PriceHistory::default();
+        }
+        Store::open(&options.database)
+            .and_then(|store| PriceHistory::load(&store, &options.seller))
+            .unwrap_or_else(|e| {
+                error!("Failed to load price history: {}", e);
+                PriceHistory::default()
+            })
+    }
+
+    /// Shows `listings` loaded from the exported file `path` instead of scraping.
+    pub fn view_listings(&mut self, path: PathBuf, listings: Vec<Listing>) {
+        info!("Viewing {} listings from {}", listings.len(), path.display());
+        self.listings = listings;
+        self.viewed_file = Some(path);
+        self.state = AppState::Running;
+    }
+
+    /// Opens the diff screen for `diff` instead of scraping.
+    pub fn show_diff(&mut self, diff: ListingDiff) {
+        self.diff = Some(diff);
+        self.state = AppState::Diff;
+    }
+
+    /// Loads the checkpoint of an unfinished scrape of `seller`, if there is one.
+    fn load_checkpoint(seller: &str) -> Option<Checkpoint> {
+        match Checkpoint::load(&Checkpoint::path_for(seller)) {
+            Ok(Some(checkpoint)) => {
+                info!(
+                    "Resuming {} from checkpoint with {}/{} listings enriched",
+                    seller,
+                    checkpoint.enriched_item_ids.len(),
+                    checkpoint.listings.len()
+                );
+                Some(checkpoint)
+            }
+            Ok(None) => {
+                info!("No checkpoint found for {}, starting a fresh scrape", seller);
+                None
+            }
+            Err(e) => {
+                error!("Failed to load checkpoint for {}: {}", seller, e);
+                None
+            }
+        }
+    }
+
+    /// Writes the current checkpoint to disk, logging any failure.
+    fn save_checkpoint(&mut self) {
+        if let Some(checkpoint) = &mut self.checkpoint {
+            let path = Checkpoint::path_for(&checkpoint.seller);
+            if let Err(e) = checkpoint.save(&path) {
+                error!("Failed to save checkpoint to {}: {}", path.display(), e);
+            }
+        }
     }
 
     /// Run the application's main loop.
     pub async fn run(mut self, mut terminal: DefaultTerminal) -> color_eyre::Result<()> {
-        self.events.send(AppEvent::Connect);
+        // Only scrape when starting on the loading screen; viewer modes start elsewhere
+        if self.state == AppState::Loading {
+            self.events.send(AppEvent::RunStage(Stage::Connect));
+        }
         while self.running {
             terminal.draw(|frame| frame.render_widget(&self, frame.area()))?;
             match self.events.next().await? {
@@ -212,11 +491,29 @@ impl App {
                 },
                 Event::App(app_event) => match app_event {
                     AppEvent::Quit => self.quit().await?,
-                    AppEvent::Connect => self.connect().await?,
-                    AppEvent::ClientReady => {
-                        self.events.send(AppEvent::Init(
-                            "https://www.ebay.com/usr/thriftngo5".to_string(),
-                        ));
+                    AppEvent::RunStage(stage) => self.run_stage(stage).await,
+                    AppEvent::StageProgress(stage, done, total) => {
+                        self.pipeline.advance(stage, done, total);
+                    }
+                    AppEvent::StageFinished(stage, summary) => {
+                        info!("✅ Stage {} finished: {}", stage, summary);
+                        self.pipeline.finish(stage, summary);
+                        self.advance_pipeline(stage);
+                    }
+                    AppEvent::StageFailed(stage, error) => {
+                        error!("Stage {} failed: {}", stage, error);
+                        self.pipeline.fail(stage, error.clone());
+                        if self.captcha_gate.is_aborted() && stage != Stage::Export {
+                            // Nothing more can be scraped after an unsolved challenge, so save what there is
+                            self.finish_early(stage, "aborted after an unsolved CAPTCHA");
+                        } else {
+                            self.progress_message = format!(
+                                "❌ {} failed: {} (r: retry{})",
+                                stage,
+                                error,
+                                if stage.is_skippable() { ", s: skip" } else { "" }
+                            );
+                        }
                     }
                     AppEvent::ScrapeFeedback(feedback_text) => {
                         self.feedback_score = Some(feedback_text.clone());
@@ -232,13 +529,16 @@ impl App {
                             self.items_sold.unwrap_or(0)
                         );
                     }
-                    AppEvent::SetProgress(progress, message) => {
-                        self.progress = progress;
-                        self.progress_message = message;
+                    AppEvent::StageCancelled(stage, summary) => {
+                        info!("⏹️ Stage {} cancelled: {}", stage, summary);
+                        self.pipeline.cancel(stage, summary);
+                        // A challenge the stage was waiting on no longer matters
+                        self.captcha = None;
+                        self.waiting_for_user_input = false;
+                        self.finish_early(stage, "cancelled by user");
                     }
-                    AppEvent::Init(url) => {
-                        self.navigate_to_public_page(url.clone()).await?;
-                        self.start_captcha_monitoring().await?;
+                    AppEvent::SetStatus(message) => {
+                        self.progress_message = message;
                     }
                     AppEvent::ScrapeFollowerCount(follower_count) => {
                         self.follower_count = Some(follower_count);
@@ -249,14 +549,12 @@ impl App {
                     }
                     AppEvent::GeckodriverStarted => {
                         info!("Geckodriver started successfully");
-                        self.events.send(AppEvent::ClientReady);
                     }
                     AppEvent::GeckodriverError(error) => {
                         self.progress_message = format!("Geckodriver error: {}", error);
                     }
                     AppEvent::WebDriverConnected => {
                         info!("WebDriver client connected");
-                        self.events.send(AppEvent::ClientReady);
                     }
                     AppEvent::WebDriverError(error) => {
                         self.progress_message = format!("WebDriver error: {}", error);
@@ -270,175 +568,62 @@ impl App {
                     AppEvent::NavigationError(error) => {
                         self.progress_message = format!("Navigation error: {}", error);
                     }
-                    AppEvent::CaptchaDetected => {
-                        info!("🚨 CAPTCHA detected - waiting for user to solve");
-                        self.captcha_detected = true;
+                    AppEvent::CaptchaDetected(challenge) => {
+                        info!("🚨 {} - waiting for user to solve", challenge);
+                        self.alert_challenge(&challenge);
+                        self.captcha = Some(ActiveChallenge {
+                            challenge: challenge.clone(),
+                            since: Instant::now(),
+                        });
                         self.waiting_for_user_input = true;
-                        self.events.send(AppEvent::SetProgress(
-                            self.progress,
-                            "⚠️  CAPTCHA detected! Please solve it manually, then it will automatically continue...".to_string(),
-                        ));
+                        self.events.send(AppEvent::SetStatus(format!(
+                            "⚠️  {} detected! Please solve it manually, then it will automatically continue...",
+                            challenge.kind
+                        )));
                     }
                     AppEvent::CaptchaResolved => {
                         info!("✅ CAPTCHA resolved - continuing scraping");
-                        self.captcha_detected = false;
+                        self.captcha = None;
                         self.waiting_for_user_input = false;
-                        
-                        let client = self.client.clone();
-                        let sender = self.events.sender.clone();
-                        
-                        tokio::spawn(async move {
-                            let _ = sender.send(Event::App(AppEvent::SetProgress(
-                                0.4,
-                                "📦 Scraping items sold...".to_string(),
-                            )));
-                            
-                            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                            
-                            if let Some(client) = &client {
-                                match Self::scrape_items_sold_static(&client).await {
-                                    Ok(items_sold) => {
-                                        let _ = sender.send(Event::App(AppEvent::ScrapeItemsSold(items_sold)));
-                                    }
-                                    Err(e) => {
-                                        log::error!("Failed to scrape items sold: {}", e);
-                                    }
-                                }
-                            }
-                            
-                            let _ = sender.send(Event::App(AppEvent::SetProgress(
-                                0.6,
-                                "⭐ Scraping feedback score...".to_string(),
-                            )));
-                            
-                            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                            
-                            if let Some(client) = &client {
-                                match Self::scrape_feedback_static(&client).await {
-                                    Ok(feedback_score) => {
-                                        let _ = sender.send(Event::App(AppEvent::ScrapeFeedback(feedback_score)));
-                                    }
-                                    Err(e) => {
-                                        log::error!("Failed to scrape feedback: {}", e);
-                                    }
-                                }
-                            }
-                            
-                            let _ = sender.send(Event::App(AppEvent::SetProgress(
-                                0.8,
-                                "👥 Scraping follower count...".to_string(),
-                            )));
-                            
-                            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                            
-                            if let Some(client) = &client {
-                                match Self::scrape_follower_count_static(&client).await {
-                                    Ok(follower_count) => {
-                                        let _ = sender.send(Event::App(AppEvent::ScrapeFollowerCount(follower_count)));
-                                    }
-                                    Err(e) => {
-                                        log::error!("Failed to scrape follower count: {}", e);
-                                    }
-                                }
-                            }
-                            
-                            let _ = sender.send(Event::App(AppEvent::SetProgress(
-                                0.9,
-                                "🖱️ Clicking \'See All\' button...".to_string(),
-                            )));
-
-                            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-
-                            if let Some(client) = &client {
-                                match Self::click_see_all_button_static(&client).await {
-                                    Ok(_) => {}
-                                    Err(e) => {
-                                        log::error!("Failed to click \'See All\' button: {}", e);
-                                    }
-                                }
-                            }
-
-                            let _ = sender.send(Event::App(AppEvent::SetProgress(
-                                0.95,
-                                "📋 Scraping listings...".to_string(),
-                            )));
-                            
-                            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                            
-                            if let Some(client) = &client {
-                                match Self::scrape_active_listings(&client).await {
-                                    Ok(listings) => {
-                                        let _ = sender.send(Event::App(AppEvent::ScrapeListings(listings)));
-                                    }
-                                    Err(e) => {
-                                        log::error!("Failed to scrape listings: {}", e);
-                                    }
-                                }
-                            }
-                            
-                            let _ = sender.send(Event::App(AppEvent::SetProgress(
-                                1.0,
-                                "✅ Scraping complete!".to_string(),
-                            )));
-                            
-                            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-                            
-                            let _ = sender.send(Event::App(AppEvent::ScrapingComplete));
-                        });
+                        self.events.send(AppEvent::SetStatus(
+                            "✅ Challenge solved - continuing...".to_string(),
+                        ));
                     }
-                    AppEvent::ScrapingComplete => {
-                        self.state = AppState::Running;
+                    AppEvent::CaptchaTimedOut => {
+                        error!("CAPTCHA not solved in time - aborting and keeping partial results");
+                        self.captcha = None;
+                        self.waiting_for_user_input = false;
+                        self.events.send(AppEvent::SetStatus(
+                            "⏱️  CAPTCHA not solved in time - saving partial results...".to_string(),
+                        ));
                     }
-                    AppEvent::ScrapeListings(listings) => {
+                    AppEvent::ScrapeListings(listings, hits) => {
                         self.listings = listings.clone();
+                        self.selector_hits = hits;
                         // Reset selection to first item when new listings are loaded
                         self.selected_listing_index = 0;
                         self.scroll_offset = 0;
                         info!("Received {} scraped listings", listings.len());
                         
-                        // Trigger enrichment of listings
-                        self.events.send(AppEvent::EnrichListings);
+                        let mut checkpoint = Checkpoint::new(&self.options.seller, listings);
+                        checkpoint.feedback_score = self.feedback_score.clone();
+                        checkpoint.items_sold = self.items_sold;
+                        checkpoint.follower_count = self.follower_count;
+                        self.checkpoint = Some(checkpoint);
+                        self.save_checkpoint();
                     }
-                    AppEvent::EnrichListings => {
-                        let client = self.client.clone();
-                        let sender = self.events.sender.clone();
-                        let mut listings = self.listings.clone();
-                        
-                        tokio::spawn(async move {
-                            let _ = sender.send(Event::App(AppEvent::SetProgress(
-                                0.95,
-                                "🔍 Enriching listings with detailed information...".to_string(),
-                            )));
-                            
-                            if let Some(client) = &client {
-                                // Enrich each listing with detailed information
-                                let total_listings = listings.len();
-                                for (index, listing) in listings.iter_mut().enumerate() {
-                                    let _ = sender.send(Event::App(AppEvent::SetProgress(
-                                        0.95 + (0.04 * (index as f64 / total_listings as f64)),
-                                        format!("🔍 Processing listing {}/{}: {}", 
-                                               index + 1, total_listings, 
-                                               &listing.title.chars().take(30).collect::<String>()),
-                                    )));
-                                    
-                                    // Construct URL from item_id
-                                    if let Some(item_id) = &listing.item_id {
-                                        let item_url = format!("https://www.ebay.com/itm/{}", item_id);
-                                        
-                                        if let Ok((item_specifics, description)) = Self::scrape_item_details(&client, &item_url).await {
-                                            listing.item_specifics = item_specifics;
-                                            listing.description = description;
-                                        }
-                                        
-                                        // Small delay between requests
-                                        tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
-                                    }
-                                }
-                            }
-                            
-                            // Send the enriched listings for saving
-                            let _ = sender.send(Event::App(AppEvent::EnrichedListings(listings)));
-                        });
+                    AppEvent::ListingEnriched(index, listing) => {
+                        self.enrichment_cache.insert(&listing);
+                        if let Some(checkpoint) = &mut self.checkpoint {
+                            checkpoint.mark_enriched(index, *listing);
+                        }
+                        self.save_checkpoint();
+                    }
+                    AppEvent::WorkerProgress(worker, status) => {
+                        if self.worker_status.len() <= worker {
+                            self.worker_status.resize(worker + 1, String::new());
+                        }
+                        self.worker_status[worker] = status;
                     }
                     AppEvent::EnrichedListings(listings) => {
                         self.listings = listings.clone();
@@ -454,26 +639,21 @@ impl App {
                         }
                         info!("Received {} enriched listings", listings.len());
                         
-                        let filename = format!("ebay_listings_{}.csv", 
-                            Utc::now().format("%Y%m%d_%H%M%S"));
-                        
-                        if let Err(e) = self.save_listings_to_csv(&filename) {
-                            error!("Failed to save listings to CSV: {}", e);
-                        } else {
-                            info!("Successfully saved {} listings to {}", listings.len(), filename);
+                        if let Err(e) = self.enrichment_cache.save(&EnrichmentCache::path()) {
+                            error!("Failed to save enrichment cache: {}", e);
                         }
-                        
-                        self.events.send(AppEvent::SetProgress(1.0, "✅ Scraping complete!".to_string()));
-                        let _ = tokio::spawn(async {
-                            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-                        });
-                        self.events.send(AppEvent::ScrapingComplete);
                     }
                     AppEvent::ClickSeeAll => {
                         let client = self.client.clone();
-                        tokio::spawn(async move {
+                        let rate_limiter = self.rate_limiter.clone();
+                        let cancel = self.cancel.clone();
+                        self.tasks.spawn(async move {
                             if let Some(client) = &client {
-                                match Self::click_see_all_button_static(&client).await {
+                                let clicked = tokio::select! {
+                                    _ = cancel.cancelled() => return,
+                                    clicked = Self::click_see_all_button_static(client, &rate_limiter) => clicked,
+                                };
+                                match clicked {
                                     Ok(_) => {}
                                     Err(e) => {
                                         log::error!("Failed to click see all button: {}", e);
@@ -490,6 +670,9 @@ impl App {
 
     /// Handles the key events and updates the state of [`App`].
     pub fn handle_key_events(&mut self, key_event: KeyEvent) -> color_eyre::Result<()> {
+        if self.state == AppState::Diff {
+            return self.handle_diff_key_events(key_event);
+        }
         match key_event.code {
             KeyCode::Esc | KeyCode::Char('q') => self.events.send(AppEvent::Quit),
             KeyCode::Char('c' | 'C') if key_event.modifiers == KeyModifiers::CONTROL => {
@@ -657,6 +840,13 @@ impl App {
                     }
                 }
             }
+            KeyCode::Char('c') if self.state == AppState::Loading => self.cancel_stage(),
+            KeyCode::Char('r') if self.state == AppState::Loading => self.retry_stage(),
+            KeyCode::Char('s') if self.state == AppState::Loading => self.skip_stage(),
+            KeyCode::Char('d') if self.state == AppState::Running && self.diff.is_some() => {
+                self.scroll_view_state.scroll_to_top();
+                self.state = AppState::Diff;
+            }
             KeyCode::Tab => {
                 // Switch between sections (only when not locked)
                 if !self.section_locked {
@@ -671,50 +861,429 @@ impl App {
         Ok(())
     }
 
+    /// Handles key events on the diff screen.
+    fn handle_diff_key_events(&mut self, key_event: KeyEvent) -> color_eyre::Result<()> {
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.events.send(AppEvent::Quit),
+            KeyCode::Char('c' | 'C') if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.events.send(AppEvent::Quit)
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.scroll_view_state.scroll_down(),
+            KeyCode::Up | KeyCode::Char('k') => self.scroll_view_state.scroll_up(),
+            KeyCode::PageDown => self.scroll_view_state.scroll_page_down(),
+            KeyCode::PageUp => self.scroll_view_state.scroll_page_up(),
+            KeyCode::Home => self.scroll_view_state.scroll_to_top(),
+            KeyCode::End => self.scroll_view_state.scroll_to_bottom(),
+            // Go back to the dashboard when the diff was opened from a scrape
+            KeyCode::Char('d') if !self.listings.is_empty() => {
+                self.scroll_view_state.scroll_to_top();
+                self.state = AppState::Running;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
     /// Handles the tick event of the terminal.
     pub fn tick(&self) {}
 
-    /// Monitors the current page URL for CAPTCHA challenges and handles the scraping workflow.
-    /// This runs asynchronously and triggers appropriate events when CAPTCHA is detected or resolved.
-    pub async fn start_captcha_monitoring(&mut self) -> color_eyre::Result<()> {
-        if let Some(client) = self.client.clone() {
-            let sender = self.events.sender.clone();
-            
-            tokio::spawn(async move {
-                let mut captcha_detected = false;
-                
-                loop {
-                    if let Ok(current_url) = client.current_url().await {
-                        let url_has_captcha = current_url.to_string().to_lowercase().contains("captcha");
-                        
-                        if url_has_captcha && !captcha_detected {
-                            // First time detecting captcha
-                            info!("🔍 CAPTCHA detected in URL: {}", current_url);
-                            captcha_detected = true;
-                            let _ = sender.send(Event::App(AppEvent::CaptchaDetected));
-                        } else if !url_has_captcha && captcha_detected {
-                            // CAPTCHA was resolved
-                            info!("✅ CAPTCHA no longer detected - continuing");
-                            let _ = sender.send(Event::App(AppEvent::CaptchaResolved));
-                            break;
-                        } else if !url_has_captcha && !captcha_detected {
-                            // No captcha detected from the start - proceed immediately
-                            let _ = sender.send(Event::App(AppEvent::CaptchaResolved));
-                            break;
-                        }
-                        
-                        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-                    } else {
-                        break;
+    /// Starts `stage` of the scrape pipeline. Stages that talk to eBay run in the background and
+    /// report back with [`AppEvent::StageFinished`] or [`AppEvent::StageFailed`].
+    pub async fn run_stage(&mut self, stage: Stage) {
+        info!("▶️ Starting stage: {}", stage);
+        self.pipeline.start(stage);
+        self.stage_cancel = self.cancel.child_token();
+        self.progress_message = match stage {
+            Stage::Connect => "🚀 Starting geckodriver...",
+            Stage::SellerStats => "🏪 Scraping seller stats...",
+            Stage::ListingPages => "📋 Scraping listings...",
+            Stage::Enrich => "🔍 Enriching listings with detailed information...",
+            Stage::Export => "💾 Saving results...",
+        }
+        .to_string();
+
+        match stage {
+            Stage::Connect => match self.connect().await {
+                Ok(()) => self.events.send(AppEvent::StageFinished(
+                    stage,
+                    "WebDriver session ready".to_string(),
+                )),
+                Err(e) => self.events.send(AppEvent::StageFailed(stage, e.to_string())),
+            },
+            Stage::SellerStats => self.start_seller_stats(),
+            Stage::ListingPages => self.start_listing_pages(),
+            Stage::Enrich => self.start_enrichment(),
+            Stage::Export => match self.export_results() {
+                Ok(summary) => self.events.send(AppEvent::StageFinished(stage, summary)),
+                Err(e) => self.events.send(AppEvent::StageFailed(stage, e.to_string())),
+            },
+        }
+    }
+
+    /// Moves on to the stage after `stage`, or shows the dashboard once every stage is over.
+    fn advance_pipeline(&mut self, stage: Stage) {
+        let Some(mut next) = stage.next() else {
+            self.progress_message = "✅ Scraping complete!".to_string();
+            self.state = AppState::Running;
+            return;
+        };
+        // A checkpoint can only exist this early when resuming, so skip the store page
+        if stage == Stage::Connect
+            && let Some(checkpoint) = &self.checkpoint
+        {
+            self.feedback_score = checkpoint.feedback_score.clone();
+            self.items_sold = checkpoint.items_sold;
+            self.follower_count = checkpoint.follower_count;
+            self.listings = checkpoint.listings.clone();
+            self.pipeline.restore(Stage::SellerStats);
+            self.pipeline.restore(Stage::ListingPages);
+            next = Stage::Enrich;
+        }
+        self.events.send(AppEvent::RunStage(next));
+    }
+
+    /// Skips every stage between `stage` and the export for `reason`, then exports the results
+    /// gathered so far.
+    fn finish_early(&mut self, stage: Stage, reason: &str) {
+        if stage == Stage::Export {
+            self.advance_pipeline(stage);
+            return;
+        }
+        let mut next = stage.next();
+        while let Some(skipped) = next.filter(|&next| next != Stage::Export) {
+            self.pipeline.skip(skipped, reason);
+            next = skipped.next();
+        }
+        self.events.send(AppEvent::RunStage(Stage::Export));
+    }
+
+    /// Cancels the stage running in the background. Whatever it scraped so far is kept and
+    /// exported, and the dashboard is shown afterwards.
+    fn cancel_stage(&mut self) {
+        if let Some(stage) = self.pipeline.running() {
+            info!("Cancelling stage {}", stage);
+            self.stage_cancel.cancel();
+            self.progress_message = format!("⏹️ Cancelling {}...", stage);
+        }
+    }
+
+    /// Runs the failed stage again.
+    fn retry_stage(&mut self) {
+        if let Some(stage) = self.pipeline.failed() {
+            info!("Retrying stage {}", stage);
+            self.events.send(AppEvent::RunStage(stage));
+        }
+    }
+
+    /// Gives up on the failed stage and continues with the next one.
+    fn skip_stage(&mut self) {
+        if let Some(stage) = self.pipeline.failed()
+            && stage.is_skippable()
+        {
+            info!("Skipping stage {}", stage);
+            self.pipeline.skip(stage, "skipped by user");
+            self.advance_pipeline(stage);
+        }
+    }
+
+    /// Returns the URL of the seller's store page.
+    fn store_url(&self) -> String {
+        self.options.marketplace.store_url(&self.options.seller)
+    }
+
+    /// Scrapes items sold, feedback and followers from the store page in the background. Loading
+    /// the page runs through the CAPTCHA gate, so a challenge pauses the stage until the user
+    /// solves it and the page is then loaded again. The stage only fails if none of the stats
+    /// were found.
+    fn start_seller_stats(&mut self) {
+        let stage = Stage::SellerStats;
+        let sender = self.events.sender.clone();
+        let Some(client) = self.client.clone() else {
+            let _ = sender.send(Event::App(AppEvent::StageFailed(stage, "no browser session".to_string())));
+            return;
+        };
+        let rate_limiter = self.rate_limiter.clone();
+        let gate = self.captcha_gate.clone();
+        let url = self.store_url();
+        let cancel = self.stage_cancel.clone();
+        let marketplace = self.options.marketplace;
+
+        self.tasks.spawn(async move {
+            let page = tokio::select! {
+                _ = cancel.cancelled() => None,
+                page = gate.guard(&client, || Self::fetch_store_page(&client, &rate_limiter, &url)) => Some(page),
+            };
+
+            let result = match page {
+                None => AppEvent::StageCancelled(stage, "cancelled before the store page loaded".to_string()),
+                Some(Err(e)) => AppEvent::StageFailed(stage, e.to_string()),
+                Some(Ok(html)) => {
+                    let _ = sender.send(Event::App(AppEvent::StageProgress(stage, 1, 2)));
+                    let stats = Self::parse_seller_stats(&html, marketplace);
+                    if let Some(items_sold) = stats.items_sold {
+                        let _ = sender.send(Event::App(AppEvent::ScrapeItemsSold(items_sold)));
                     }
+                    if let Some(feedback_score) = stats.feedback_score.clone() {
+                        let _ = sender.send(Event::App(AppEvent::ScrapeFeedback(feedback_score)));
+                    }
+                    if let Some(follower_count) = stats.follower_count {
+                        let _ = sender.send(Event::App(AppEvent::ScrapeFollowerCount(follower_count)));
+                    }
+                    let _ = sender.send(Event::App(AppEvent::StageProgress(stage, 2, 2)));
+                    Self::seller_stats_result(&stats)
                 }
-            });
+            };
+            let _ = sender.send(Event::App(result));
+        });
+    }
+
+    /// Opens the store's listings and parses every listing card in the background.
+    fn start_listing_pages(&mut self) {
+        let stage = Stage::ListingPages;
+        let sender = self.events.sender.clone();
+        let Some(client) = self.client.clone() else {
+            let _ = sender.send(Event::App(AppEvent::StageFailed(stage, "no browser session".to_string())));
+            return;
+        };
+        let rate_limiter = self.rate_limiter.clone();
+        let selectors = self.selectors.clone();
+        let gate = self.captcha_gate.clone();
+        let url = self.store_url();
+        let cancel = self.stage_cancel.clone();
+        let exclude_sponsored = self.options.exclude_sponsored;
+        let marketplace = self.options.marketplace;
+
+        self.tasks.spawn(async move {
+            let scrape = gate.guard(&client, || async {
+                    Self::open_page_static(&client, &rate_limiter, &url).await?;
+                    let _ = sender.send(Event::App(AppEvent::StageProgress(stage, 1, 3)));
+
+                    let _ = sender.send(Event::App(AppEvent::SetStatus(
+                        "🖱️ Clicking \'See All\' button...".to_string(),
+                    )));
+                    // Small stores list everything on the store page and have no button
+                    if let Err(e) = Self::click_see_all_button_static(&client, &rate_limiter).await {
+                        log::error!("Failed to click \'See All\' button: {}", e);
+                    }
+                    let _ = sender.send(Event::App(AppEvent::StageProgress(stage, 2, 3)));
+
+                    let _ = sender.send(Event::App(AppEvent::SetStatus("📋 Scraping listings...".to_string())));
+                    Self::scrape_active_listings(&client, &selectors, marketplace).await
+                });
+            let listings = tokio::select! {
+                _ = cancel.cancelled() => {
+                    let _ = sender.send(Event::App(AppEvent::StageCancelled(
+                        stage,
+                        "no listings scraped".to_string(),
+                    )));
+                    return;
+                }
+                listings = scrape => listings,
+            };
+
+            match listings {
+                Ok((mut listings, hits)) => {
+                    let sponsored = listings.iter().filter(|listing| listing.sponsored).count();
+                    let summary = if exclude_sponsored && sponsored > 0 {
+                        listings.retain(|listing| !listing.sponsored);
+                        info!("Excluded {} sponsored listings", sponsored);
+                        format!("{} listings found, {} sponsored excluded", listings.len(), sponsored)
+                    } else if sponsored > 0 {
+                        format!("{} listings found, {} sponsored", listings.len(), sponsored)
+                    } else {
+                        format!("{} listings found", listings.len())
+                    };
+                    let _ = sender.send(Event::App(AppEvent::StageProgress(stage, 3, 3)));
+                    let _ = sender.send(Event::App(AppEvent::ScrapeListings(listings, hits)));
+                    let _ = sender.send(Event::App(AppEvent::StageFinished(stage, summary)));
+                }
+                Err(e) => {
+                    let _ = sender.send(Event::App(AppEvent::StageFailed(stage, e.to_string())));
+                }
+            }
+        });
+    }
+
+    /// Visits the item page of every listing in the background, using the main session and the
+    /// configured number of extra pooled sessions.
+    fn start_enrichment(&mut self) {
+        let stage = Stage::Enrich;
+        let sender = self.events.sender.clone();
+        let Some(client) = self.client.clone() else {
+            let _ = sender.send(Event::App(AppEvent::StageFailed(stage, "no browser session".to_string())));
+            return;
+        };
+        let rate_limiter = self.rate_limiter.clone();
+        let selectors = self.selectors.clone();
+        let gate = self.captcha_gate.clone();
+        let workers = self.options.workers.max(1);
+        let mut listings = self.listings.clone();
+        // Listings restored from older checkpoints may only have an item ID
+        for listing in listings.iter_mut().filter(|listing| listing.url.is_none()) {
+            listing.url = listing.item_url(self.options.marketplace);
         }
-        Ok(())
+        // Skip items enriched before a resume and items whose cached details are still fresh
+        let mut skip = self
+            .checkpoint
+            .as_ref()
+            .map(|checkpoint| checkpoint.enriched_item_ids.clone())
+            .unwrap_or_default();
+        skip.extend(self.enrichment_cache.apply(&mut listings, self.options.cache_ttl()));
+        if let Some(checkpoint) = &mut self.checkpoint {
+            checkpoint.stage = CheckpointStage::Enriching;
+        }
+        self.save_checkpoint();
+        let cancel = self.stage_cancel.clone();
+
+        self.tasks.spawn(async move {
+            // The main session is always the first worker; extra sessions join it
+            let session_pool = SessionPool::launch(workers - 1).await;
+            let mut clients = vec![client];
+            clients.extend(session_pool.clients());
+
+            let total = listings.len();
+            let skipped = listings
+                .iter()
+                .filter(|listing| listing.item_id.as_ref().is_some_and(|item_id| skip.contains(item_id)))
+                .count();
+            let (listings, enriched) = pool::enrich_listings(
+                clients,
+                listings,
+                &skip,
+                rate_limiter,
+                selectors,
+                gate,
+                sender.clone(),
+                cancel.clone(),
+            )
+            .await;
+            session_pool.shutdown().await;
+
+            // Send the enriched listings for saving, even the partial ones of a cancelled stage
+            let _ = sender.send(Event::App(AppEvent::EnrichedListings(listings)));
+            let result = if cancel.is_cancelled() {
+                AppEvent::StageCancelled(
+                    stage,
+                    format!("{}/{} listings enriched", enriched, total - skipped),
+                )
+            } else {
+                AppEvent::StageFinished(
+                    stage,
+                    format!("{} listings enriched, {} already up to date", enriched, skipped),
+                )
+            };
+            let _ = sender.send(Event::App(result));
+        });
+    }
+
+    /// Saves the results to the configured export file and database. Once they are safely on
+    /// disk, the checkpoint is no longer needed; an aborted or cancelled scrape keeps it so the
+    /// missing items can be resumed later. Without any listings nothing is saved.
+    fn export_results(&mut self) -> color_eyre::Result<String> {
+        self.report_coverage();
+        let mut saved_to = Vec::new();
+
+        if self.listings.is_empty() {
+            info!("No listings to save");
+        } else if self.options.storage.writes_file() {
+            let path = self.options.export_path();
+            self.export_listings(&path)
+                .map_err(|e| eyre!("failed to export listings to {}: {}", path.display(), e))?;
+            saved_to.push(path.display().to_string());
+        }
+
+        if !self.listings.is_empty() && self.options.storage.writes_database() {
+            let run_id = self
+                .record_run()
+                .map_err(|e| eyre!("failed to record run in {}: {}", self.options.database.display(), e))?;
+            saved_to.push(format!("run {} in {}", run_id, self.options.database.display()));
+        }
+
+        if !self.captcha_gate.is_aborted() && !self.pipeline.was_cancelled() {
+            if let Err(e) = Checkpoint::remove(&Checkpoint::path_for(&self.options.seller)) {
+                error!("Failed to remove checkpoint: {}", e);
+            }
+            self.checkpoint = None;
+        }
+
+        if saved_to.is_empty() {
+            return Ok("No listings, nothing exported".to_string());
+        }
+        let mut summary = format!("{} listings saved to {}", self.listings.len(), saved_to.join(" and "));
+        if !self.coverage_drops.is_empty() {
+            summary.push_str(&format!(", ⚠️ {} fields dropped in coverage", self.coverage_drops.len()));
+        }
+        Ok(summary)
     }
 
-    /// Set running to false to quit the application.
+    /// Computes how many listings have each field, logs it and warns about fields whose
+    /// coverage dropped sharply since the seller's previous run, which usually means a
+    /// selector stopped matching.
+    fn report_coverage(&mut self) {
+        let report = CoverageReport::compute(&self.listings, &self.selector_hits);
+        for coverage in &report.fields {
+            info!(
+                "Coverage of {}: {}/{} ({:.0}%) via {}",
+                coverage.field,
+                coverage.filled,
+                coverage.total,
+                coverage.rate() * 100.0,
+                coverage.selector.as_deref().unwrap_or("-")
+            );
+        }
+
+        let path = CoverageReport::path_for(&self.options.seller);
+        // Restored listings were parsed by an earlier process, so no selector hits were recorded
+        // and there is nothing to tell about the selectors
+        let previous = if self.pipeline.was_restored(Stage::ListingPages) {
+            info!("Listing pages were restored from a checkpoint, not comparing selector coverage");
+            None
+        } else {
+            CoverageReport::load(&path).unwrap_or_else(|e| {
+                error!("Failed to load previous coverage report: {}", e);
+                None
+            })
+        };
+        self.coverage_drops = previous.map(|previous| report.drops(&previous)).unwrap_or_default();
+        for drop in &self.coverage_drops {
+            log::warn!("Coverage of {}, a selector may have stopped matching", drop);
+        }
+
+        // Only a complete run is a baseline; an empty or partial one would hide the next drop
+        if self.is_complete_run()
+            && !self.listings.is_empty()
+            && let Err(e) = report.save(&path)
+        {
+            error!("Failed to save coverage report: {}", e);
+        }
+        self.coverage = Some(report);
+    }
+
+    /// Returns true if no stage of this run was cancelled or skipped and no CAPTCHA aborted it,
+    /// so its listings cover the whole store.
+    fn is_complete_run(&self) -> bool {
+        !self.pipeline.was_cancelled() && !self.pipeline.was_skipped() && !self.captcha_gate.is_aborted()
+    }
+
+    /// Cancels every background task and waits for them to stop driving the browser, then closes
+    /// the session and sets running to false to quit the application.
     pub async fn quit(&mut self) -> color_eyre::Result<()> {
+        self.cancel.cancel();
+        if !self.tasks.is_empty() {
+            info!("Waiting for {} background tasks to stop", self.tasks.len());
+            let stopped = tokio::time::timeout(TASK_SHUTDOWN_TIMEOUT, async {
+                while let Some(result) = self.tasks.join_next().await {
+                    if let Err(e) = result {
+                        error!("Background task failed: {}", e);
+                    }
+                }
+            })
+            .await;
+            if stopped.is_err() {
+                error!("Background tasks did not stop in time, aborting them");
+                self.tasks.abort_all();
+            }
+        }
         if let Some(client) = self.client.take() {
             info!("Quitting fantoccini client");
             client.close().await?;
@@ -727,144 +1296,111 @@ impl App {
         Ok(())
     }
 
-    /// Connect to the webdriver client.
+    /// Connect to the webdriver client. A geckodriver left over from a failed attempt is
+    /// stopped first, so the stage can be retried.
     pub async fn connect(&mut self) -> color_eyre::Result<()> {
+        if let Some(mut child) = self.geckodriver.take() {
+            let _ = child.kill();
+        }
         info!("Starting geckodriver");
-        self.events.send(AppEvent::SetProgress(
-            0.1,
-            "🚀 Starting geckodriver...".to_string(),
-        ));
         let child = Command::new("./geckodriver")
             .stdout(std::process::Stdio::null())
             .stderr(std::process::Stdio::null())
             .spawn()?;
         self.geckodriver = Some(child);
-        self.events.send(AppEvent::SetProgress(
-            0.2,
-            "🔗 Connecting to fantoccini...".to_string(),
-        ));
+        self.events.send(AppEvent::StageProgress(Stage::Connect, 1, 2));
         info!("Connecting to webdriver");
-        match ClientBuilder::native()
+        let client = ClientBuilder::native()
             .connect("http://localhost:4444")
-            .await
-        {
-            Ok(client) => {
-                client.minimize_window().await?;
-                self.client = Some(client);
-                self.events.send(AppEvent::SetProgress(
-                    0.3,
-                    "🌐 Navigating to ebay.com...".to_string(),
-                ));
-                self.events.send(AppEvent::ClientReady);
-                info!("Webdriver client connected");
-            }
-            Err(e) => {
-                error!("Failed to connect to webdriver: {}", e);
-                self.quit().await?;
-            }
-        }
+            .await?;
+        client.minimize_window().await?;
+        self.client = Some(client);
+        self.events.send(AppEvent::StageProgress(Stage::Connect, 2, 2));
+        info!("Webdriver client connected");
         Ok(())
     }
 
-    
-
-    /// Navigates the WebDriver client to the specified eBay seller page.
-    pub async fn navigate_to_public_page(&mut self, url: String) -> color_eyre::Result<()> {
+    /// Navigates the WebDriver client to `url` once the rate limiter allows it and makes sure
+    /// eBay did not serve a challenge instead.
+    pub async fn open_page_static(client: &Client, rate_limiter: &RateLimiter, url: &str) -> color_eyre::Result<()> {
         info!("Navigating to {}", url);
-        if let Some(client) = &mut self.client {
-            client.goto(&url).await?;
-            info!("Navigated to {}", url);
-        }
+        rate_limiter.acquire().await;
+        client.goto(url).await?;
+        captcha::check_page(client).await?;
+        info!("Navigated to {}", url);
         Ok(())
     }
 
-    /// Static version of scrape_items_sold for use in async tasks
-    pub async fn scrape_items_sold_static(client: &Client) -> color_eyre::Result<u32> {
-        info!("Attempting to scrape items sold");
-        match client
+    /// Opens the store page and returns its HTML once the seller card has rendered, or after
+    /// a short wait if it never does.
+    async fn fetch_store_page(client: &Client, rate_limiter: &RateLimiter, url: &str) -> color_eyre::Result<String> {
+        Self::open_page_static(client, rate_limiter, url).await?;
+        if let Err(e) = client
             .wait()
             .at_most(std::time::Duration::from_secs(2))
-            .for_element(fantoccini::Locator::Css(
-                "div[title*='items sold'] > span",
-            ))
+            .for_element(fantoccini::Locator::Css(".str-seller-card__store-stats-content"))
             .await
         {
-            Ok(sold_items_element) => {
-                let sold_items_text = sold_items_element.text().await?;
-                info!("Raw sold items text: {}", sold_items_text);
-                let sold_items_count =
-                    sold_items_text.replace(",", "").parse::<u32>().unwrap_or_else(|e| {
-                        error!("Failed to parse sold items count: {}", e);
-                        0
-                    });
-                Ok(sold_items_count)
-            }
-            Err(e) => {
-                info!("Could not find sold items element: {}", e);
-                Ok(0)
-            }
+            info!("Could not find the store stats: {}", e);
         }
+        Ok(client.source().await?)
     }
 
-    /// Static version of scrape_feedback for use in async tasks
-    pub async fn scrape_feedback_static(client: &Client) -> color_eyre::Result<String> {
-        info!("Attempting to scrape feedback");
-        match client
-            .wait()
-            .at_most(std::time::Duration::from_secs(2))
-            .for_element(fantoccini::Locator::Css(
-                ".str-seller-card__feedback-link",
-            ))
-            .await
-        {
-            Ok(feedback_element) => {
-                let feedback_text = feedback_element.text().await?;
-                info!("Feedback text: {}", feedback_text);
-                Ok(feedback_text)
+    /// Reads items sold, feedback and followers from the HTML of a store page. A stat whose
+    /// element is missing or unreadable is left out.
+    pub fn parse_seller_stats(html: &str, marketplace: Marketplace) -> SellerStats {
+        let document = Html::parse_document(html);
+        let text = |selector: &str| -> Option<String> {
+            let selector = Selector::parse(selector).ok()?;
+            let element = document.select(&selector).next()?;
+            let text = element.text().collect::<Vec<_>>().join(" ");
+            let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            (!text.is_empty()).then_some(text)
+        };
+
+        let items_sold = text(&format!("div[title*='{}'] > span", marketplace.vocabulary().items_sold))
+            .and_then(|text| {
+                info!("Raw sold items text: {}", text);
+                text.replace(',', "").parse::<u32>().ok()
+            });
+        let feedback_score = text(".str-seller-card__feedback-link");
+        let follower_count = text(".str-seller-card__store-stats-content > div:nth-child(3)").and_then(|text| {
+            info!("Raw follower text: {}", text);
+            if !text.to_lowercase().contains(marketplace.vocabulary().followers) {
+                log::warn!("Follower text does not mention followers, the store layout may have changed");
             }
-            Err(e) => {
-                info!("Could not find feedback element: {}", e);
-                Ok(String::new())
+            // Extract just the numeric part from text like "1 follower" or "123 followers"
+            text.split_whitespace().next()?.replace(',', "").parse::<u32>().ok()
+        });
+
+        for (name, found) in [
+            ("items sold", items_sold.is_some()),
+            ("feedback", feedback_score.is_some()),
+            ("follower count", follower_count.is_some()),
+        ] {
+            if !found {
+                error!("Could not read the {} from the store page", name);
             }
         }
+        SellerStats {
+            items_sold,
+            feedback_score,
+            follower_count,
+        }
     }
 
-    /// Static version of scrape_follower_count for use in async tasks
-    pub async fn scrape_follower_count_static(client: &Client) -> color_eyre::Result<u32> {
-        info!("Attempting to scrape follower count");
-        match client
-            .wait()
-            .at_most(std::time::Duration::from_secs(2))
-            .for_element(fantoccini::Locator::Css(
-                r#".str-seller-card__store-stats-content > div:nth-child(3)"#,
-            ))
-            .await
-        {
-            Ok(follower_element) => {
-                let follower_text = follower_element.text().await?;
-                info!("Raw follower text: {}", follower_text);
-                // Extract just the numeric part from text like "1 follower" or "123 followers"
-                let follower_count = follower_text
-                    .split_whitespace()
-                    .next()
-                    .unwrap_or("0")
-                    .replace(",", "")
-                    .parse::<u32>()
-                    .unwrap_or_else(|e| {
-                        error!("Failed to parse follower count: {}", e);
-                        0
-                    });
-                Ok(follower_count)
-            }
-            Err(e) => {
-                info!("Could not find follower element: {}", e);
-                Ok(0)
-            }
+    /// Returns how the seller stats stage ended: it only fails if none of the stats were found.
+    pub fn seller_stats_result(stats: &SellerStats) -> AppEvent {
+        let stage = Stage::SellerStats;
+        match stats.found() {
+            0 => AppEvent::StageFailed(stage, "no seller stats found on the store page".to_string()),
+            found => AppEvent::StageFinished(stage, format!("{}/3 stats found", found)),
         }
     }
 
-    /// Static version of click_see_all_button for use in async tasks
-    pub async fn click_see_all_button_static(client: &Client) -> color_eyre::Result<()> {
+    /// Static version of click_see_all_button for use in async tasks.
+    /// The click navigates to the store's listings page, so it waits on the rate limiter first.
+    pub async fn click_see_all_button_static(client: &Client, rate_limiter: &RateLimiter) -> color_eyre::Result<()> {
         info!("Attempting to click the 'see all' button");
         match client
             .wait()
@@ -875,6 +1411,7 @@ impl App {
             .await
         {
             Ok(button) => {
+                rate_limiter.acquire().await;
                 button.click().await?;
                 info!("'See all' button clicked successfully");
                 Ok(())
@@ -888,24 +1425,27 @@ impl App {
 
     /// Scrapes eBay listings from HTML content and returns a vector of Listing structs.
     /// This function parses the provided HTML and extracts listing information suitable for CSV export.
-    pub fn scrape_listings_from_html(html_content: &str) -> color_eyre::Result<Vec<Listing>> {
+    pub fn scrape_listings_from_html(html_content: &str, selectors: &SelectorRegistry) -> color_eyre::Result<Vec<Listing>> {
+        Self::parse_listings(html_content, selectors, Marketplace::default()).map(|(listings, _)| listings)
+    }
+
+    /// Same as [`App::scrape_listings_from_html`] for a page of `marketplace`, but also returns
+    /// which of the fallback selectors supplied each field.
+    pub fn parse_listings(
+        html_content: &str,
+        selectors: &SelectorRegistry,
+        marketplace: Marketplace,
+    ) -> color_eyre::Result<(Vec<Listing>, SelectorHits)> {
         let document = Html::parse_document(html_content);
+        let fields = &selectors.card_fields;
+        let vocabulary = marketplace.vocabulary();
+        let mut hits = SelectorHits::default();
         
         // Try multiple selectors to handle different eBay listing formats
-        let possible_selectors = vec![
-            "div.su-card-container",
-            "div.s-item__wrapper", 
-            "li.s-item",
-            ".str-item-card",
-            ".item-listing-cell",
-            "[data-testid='item-card']",
-            ".str-grid-item"
-        ];
-        
         let mut elements = Vec::new();
         let mut successful_selector = "";
         
-        for selector_str in &possible_selectors {
+        for selector_str in &selectors.cards {
             match Selector::parse(selector_str) {
                 Ok(selector) => {
                     let found_elements: Vec<_> = document.select(&selector).collect();
@@ -924,10 +1464,11 @@ impl App {
 
         if elements.is_empty() {
             info!("No listings found with any of the known selectors");
-            return Ok(Vec::new());
+            return Ok((Vec::new(), hits));
         }
         
         info!("Found {} listings using selector: {}", elements.len(), successful_selector);
+        hits.card_selector = Some(successful_selector.to_string());
         let mut listings = Vec::new();
 
         for (index, element) in elements.into_iter().enumerate() {
@@ -935,84 +1476,29 @@ impl App {
             info!("Processing element #{} with selector: {}", index + 1, successful_selector);
 
             // Extract title - try multiple selectors based on format
-            listing.title = Self::extract_text_from_selectors(&element, &[
-                // New su-card format
-                ".s-card__title .su-styled-text",
-                "div[role='heading'] .su-styled-text",
-                // Old s-item format
-                "div.s-item__title span[role='heading']",
-                ".s-item__title span",
-                ".s-item__title",
-                // Generic fallbacks
-                "h3",
-                ".title",
-                "[role='heading']"
-            ]).unwrap_or_default();
+            if let Some((title, selector)) = Self::extract_text_from_selectors(&element, &fields.title) {
+                listing.title = title;
+                hits.record("title", selector);
+            }
 
             // Extract price - try multiple selectors
-            listing.price = Self::extract_text_from_selectors(&element, &[
-                // New su-card format
-                ".s-card__price",
-                ".su-styled-text.primary.bold",
-                // Old s-item format  
-                "span.s-item__price",
-                ".s-item__detail--primary .s-item__price",
-                // Generic fallbacks
-                ".price"
-            ]).unwrap_or_default();
-
-            // Extract shipping cost
-            if let Some(shipping_text) = Self::extract_text_from_selectors(&element, &[
-                // New format - look for delivery/shipping text
-                ".s-card__attribute-row",
-                ".su-styled-text",
-                // Old format
-                "span.s-item__shipping",
-                ".s-item__logisticsCost",
-            ]) {
-                // Filter for text containing delivery or shipping info
-                if shipping_text.to_lowercase().contains("delivery") || 
-                   shipping_text.to_lowercase().contains("shipping") ||
-                   shipping_text.contains("$") {
-                    listing.shipping = Some(shipping_text);
-                }
+            if let Some((price, selector)) = Self::extract_text_from_selectors(&element, &fields.price) {
+                listing.price = price;
+                hits.record("price", selector);
             }
 
             // Extract condition
-            if let Some(condition_text) = Self::extract_text_from_selectors(&element, &[
-                // New format
-                ".s-card__subtitle .su-styled-text",
-                // Old format
-                "span.SECONDARY_INFO",
-            ]) {
+            if let Some((condition_text, selector)) = Self::extract_text_from_selectors(&element, &fields.condition) {
                 listing.condition = Some(condition_text);
-            }
-
-            // Extract location
-            if let Some(location_text) = Self::extract_text_from_selectors(&element, &[
-                // New format - look for "Located in" text
-                ".su-styled-text",
-                // Old format
-                ".s-item__location",
-            ]) {
-                // Filter for text containing location info
-                if location_text.to_lowercase().contains("located") || 
-                   location_text.to_lowercase().contains("from") {
-                    listing.location = Some(location_text);
-                }
+                hits.record("condition", selector);
             }
 
             // Extract seller information  
-            if let Some(seller_text) = Self::extract_text_from_selectors(&element, &[
-                // New format - seller name and feedback are separate
-                ".su-card-container__attributes__secondary .su-styled-text",
-                // Old format
-                ".s-item__etrs-text .PRIMARY",
-                ".s-item__seller-info-text",
-            ]) {
+            if let Some((seller_text, selector)) = Self::extract_text_from_selectors(&element, &fields.seller) {
                 // Parse seller name and feedback from text like "thriftngo5 95.7% positive (21)"
                 let parts: Vec<&str> = seller_text.split_whitespace().collect();
                 if !parts.is_empty() {
+                    hits.record("seller", selector);
                     listing.seller = Some(parts[0].to_string());
                     // Look for feedback percentage in the remaining text
                     let feedback_text = parts[1..].join(" ");
@@ -1023,33 +1509,76 @@ impl App {
             }
 
             // Check for "Best Offer" availability
-            listing.accepts_offers = Self::text_contains(&element, &[
-                ".su-styled-text",
-                ".s-item__dynamic", 
-                ".s-item__formatBestOfferEnabled"
-            ], "best offer") || Self::text_contains(&element, &[
-                ".su-styled-text",
-                ".s-item__dynamic"
-            ], "or best offer");
-
-            // Extract item URL from href attributes to get item ID
-            let link_selectors = vec!["a", ".su-link", ".s-item__link"];
-            for link_selector in &link_selectors {
-                if let Ok(selector) = Selector::parse(link_selector) {
-                    if let Some(link_element) = element.select(&selector).next() {
-                        if let Some(href) = link_element.value().attr("href") {
-                            // Extract item ID from URL if possible
-                            if let Some(item_id_match) = href.split("itm/").nth(1) {
-                                if let Some(item_id) = item_id_match.split('?').next() {
-                                    listing.item_id = Some(item_id.to_string());
-                                    listing.url = Some(format!("https://www.ebay.com/itm/{}", item_id));
-                                    break;
-                                }
-                            }
-                        }
+            listing.accepts_offers = Self::text_contains(&element, &fields.best_offer, vocabulary.best_offer);
+
+            // Classify every attribute row, the first row of each kind wins
+            for (text, selector) in Self::attribute_rows(&element, &fields.attribute_rows) {
+                match CardAttribute::classify(&text, vocabulary) {
+                    CardAttribute::Shipping(shipping) if listing.shipping.is_none() => {
+                        listing.shipping = Some(shipping);
+                        hits.record("shipping", selector);
+                    }
+                    CardAttribute::Location(location) if listing.location.is_none() => {
+                        listing.location = Some(location);
+                        hits.record("location", selector);
                     }
+                    // An auction with a Buy It Now option is still an auction
+                    CardAttribute::Format(ListingFormat::BuyItNow) => {
+                        listing.buy_it_now = true;
+                        listing.format.get_or_insert(ListingFormat::BuyItNow);
+                    }
+                    CardAttribute::Format(ListingFormat::BestOffer) => listing.accepts_offers = true,
+                    CardAttribute::Format(ListingFormat::Auction) => listing.format = Some(ListingFormat::Auction),
+                    CardAttribute::Bids(bids) if listing.bids.is_none() => {
+                        listing.bids = Some(bids);
+                        listing.format = Some(ListingFormat::Auction);
+                    }
+                    CardAttribute::Watchers(watchers) if listing.watchers.is_none() => {
+                        listing.watchers = Some(watchers);
+                    }
+                    CardAttribute::NewListing => listing.is_new_listing = true,
+                    CardAttribute::Sponsored => listing.sponsored = true,
+                    // The price and seller rows are already covered by their own fields
+                    CardAttribute::Other(other)
+                        if other != listing.price
+                            && !listing.seller.as_ref().is_some_and(|seller| other.contains(seller.as_str()))
+                            && !listing.notes.contains(&other) =>
+                    {
+                        listing.notes.push(other);
+                    }
+                    _ => {}
                 }
             }
+            if listing.watchers.is_none()
+                && let Some((watchers, _)) = Self::extract_text_from_selectors(&element, &fields.watchers)
+            {
+                listing.watchers = attributes::leading_count(&watchers);
+            }
+
+            // Extract item ID and canonical URL from the first link to an item page
+            for link_selector in &fields.link {
+                let Ok(selector) = Selector::parse(link_selector) else {
+                    continue;
+                };
+                let item_url = element
+                    .select(&selector)
+                    .filter_map(|link| link.value().attr("href"))
+                    .find_map(|href| ItemUrl::parse(href, marketplace.host()));
+                if let Some(item_url) = item_url {
+                    listing.url = Some(item_url.to_string());
+                    listing.item_id = Some(item_url.item_id);
+                    listing.variation_id = item_url.variation_id;
+                    hits.record("link", link_selector);
+                    break;
+                }
+            }
+
+            listing.sponsored |= Self::text_contains(&element, &fields.sponsored, vocabulary.sponsored);
+
+            if listing.is_placeholder() {
+                info!("Skipping placeholder card #{}: {} - {}", index + 1, listing.title, listing.price);
+                continue;
+            }
 
             // Only add listings that have at least a title and price
             if !listing.title.is_empty() && !listing.price.is_empty() {
@@ -1061,19 +1590,53 @@ impl App {
             }
         }
 
+        let foreign = listings
+            .iter()
+            .filter(|listing| !marketplace.matches_currency(&listing.price))
+            .count();
+        if foreign > 0 {
+            log::warn!(
+                "{} of {} prices are not in {}, check that the marketplace is right",
+                foreign,
+                listings.len(),
+                marketplace.currency()
+            );
+        }
+
         info!("Successfully scraped {} listings from HTML using selector: {}", listings.len(), successful_selector);
-        Ok(listings)
+        Ok((listings, hits))
     }
 
-    /// Helper function to extract text from the first matching selector
-    fn extract_text_from_selectors(element: &scraper::ElementRef, selectors: &[&str]) -> Option<String> {
-        for &selector_str in selectors {
+    /// Returns the text of every element matching any of `selectors`, in selector order, along
+    /// with the selector that matched it. Rows found by more than one selector are returned once.
+    fn attribute_rows<'s>(element: &scraper::ElementRef, selectors: &'s [String]) -> Vec<(String, &'s str)> {
+        let mut rows: Vec<(String, &str)> = Vec::new();
+        for selector_str in selectors {
+            let Ok(selector) = Selector::parse(selector_str) else {
+                continue;
+            };
+            for row in element.select(&selector) {
+                let text = row.text().collect::<Vec<_>>().join("").trim().to_string();
+                if !text.is_empty() && !rows.iter().any(|(seen, _)| *seen == text) {
+                    rows.push((text, selector_str));
+                }
+            }
+        }
+        rows
+    }
+
+    /// Helper function to extract text from the first matching selector, along with that selector
+    fn extract_text_from_selectors<'s>(
+        element: &scraper::ElementRef,
+        selectors: &'s [String],
+    ) -> Option<(String, &'s str)> {
+        for selector_str in selectors {
             match Selector::parse(selector_str) {
                 Ok(selector) => {
                     if let Some(elem) = element.select(&selector).next() {
                         let text = elem.text().collect::<Vec<_>>().join(" ").trim().to_string();
                         if !text.is_empty() {
-                            return Some(text);
+                            return Some((text, selector_str));
                         }
                     }
                 }
@@ -1084,12 +1647,13 @@ impl App {
     }
 
 
-    /// Helper function to check if text contains a specific substring
-    fn text_contains(element: &scraper::ElementRef, selectors: &[&str], search_text: &str) -> bool {
-        selectors.iter().any(|&selector_str| {
+    /// Helper function to check if text contains any of the given lowercase phrases
+    fn text_contains(element: &scraper::ElementRef, selectors: &[String], phrases: &[&str]) -> bool {
+        selectors.iter().any(|selector_str| {
             if let Ok(selector) = Selector::parse(selector_str) {
                 element.select(&selector).any(|elem| {
-                    elem.text().collect::<Vec<_>>().join(" ").to_lowercase().contains(search_text)
+                    let text = elem.text().collect::<Vec<_>>().join(" ").to_lowercase();
+                    phrases.iter().any(|phrase| text.contains(phrase))
                 })
             } else {
                 false
@@ -1098,25 +1662,20 @@ impl App {
     }
 
     /// Scrapes active eBay listings from the current page using the WebDriver client.
-    /// Returns a vector of structured Listing objects ready for CSV export.
-    pub async fn scrape_active_listings(client: &Client) -> color_eyre::Result<Vec<Listing>> {
+    /// Returns a vector of structured Listing objects ready for CSV export, along with the
+    /// selectors that supplied their fields.
+    pub async fn scrape_active_listings(
+        client: &Client,
+        selectors: &SelectorRegistry,
+        marketplace: Marketplace,
+    ) -> color_eyre::Result<(Vec<Listing>, SelectorHits)> {
         info!("Starting to scrape active listings from current page");
 
         // Wait a bit for page content to load
         tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
         
         // Try to wait for any potential listing elements to appear
-        let wait_selectors = vec![
-            "div.su-card-container",
-            "div.s-item__wrapper",
-            "li.s-item",
-            ".str-item-card", 
-            ".item-listing-cell",
-            "[data-testid='item-card']",
-            ".str-grid-item"
-        ];
-        
-        for selector in &wait_selectors {
+        for selector in &selectors.cards {
             if let Ok(_) = client
                 .wait()
                 .at_most(std::time::Duration::from_secs(5))
@@ -1128,75 +1687,47 @@ impl App {
             }
         }
 
-        // Get the page source HTML
+        // Get the page source HTML and make sure it is not a challenge page
         let page_source = client.source().await?;
+        captcha::check_html(client.current_url().await?.as_str(), &page_source)?;
         
         // Parse the HTML and extract listings
-        let listings = Self::scrape_listings_from_html(&page_source)?;
+        let (listings, hits) = Self::parse_listings(&page_source, selectors, marketplace)?;
         
         info!("Successfully scraped {} active listings", listings.len());
-        Ok(listings)
+        Ok((listings, hits))
     }
 
     /// Scrapes item specifics and description from an individual eBay item page.
     /// This function takes an item URL and extracts detailed information.
-    pub async fn scrape_item_details(client: &Client, item_url: &str) -> color_eyre::Result<(Vec<String>, Option<String>)> {
+    pub async fn scrape_item_details(
+        client: &Client,
+        rate_limiter: &RateLimiter,
+        selectors: &SelectorRegistry,
+        item_url: &str,
+    ) -> color_eyre::Result<(Vec<String>, Option<String>)> {
         info!("Scraping item details from: {}", item_url);
         
-        // Navigate to the item page
+        // Navigate to the item page once the rate limiter allows it
+        rate_limiter.acquire().await;
         client.goto(item_url).await?;
         
         // Wait for the page to load
         tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+        captcha::check_page(client).await?;
         
         let mut item_specifics = Vec::new();
         
         // Extract item specifics using WebDriver element finding
-        // Look for condition
-        if let Ok(condition_elem) = client
-            .wait()
-            .at_most(std::time::Duration::from_secs(2))
-            .for_element(fantoccini::Locator::Css("dl.ux-labels-values--condition .ux-labels-values__values span.ux-textspans"))
-            .await
-        {
-            if let Ok(text) = condition_elem.text().await {
-                item_specifics.push(format!("Condition: {}", text.trim()));
-            }
-        }
-        
-        // Look for brand
-        if let Ok(brand_elem) = client
-            .wait()
-            .at_most(std::time::Duration::from_secs(2))
-            .for_element(fantoccini::Locator::Css("dl.ux-labels-values--brand .ux-labels-values__values span.ux-textspans"))
-            .await
-        {
-            if let Ok(text) = brand_elem.text().await {
-                item_specifics.push(format!("Brand: {}", text.trim()));
-            }
-        }
-        
-        // Look for model
-        if let Ok(model_elem) = client
-            .wait()
-            .at_most(std::time::Duration::from_secs(2))
-            .for_element(fantoccini::Locator::Css("dl.ux-labels-values--model .ux-labels-values__values span.ux-textspans"))
-            .await
-        {
-            if let Ok(text) = model_elem.text().await {
-                item_specifics.push(format!("Model: {}", text.trim()));
-            }
-        }
-        
-        // Look for color
-        if let Ok(color_elem) = client
-            .wait()
-            .at_most(std::time::Duration::from_secs(2))
-            .for_element(fantoccini::Locator::Css("dl.ux-labels-values--color .ux-labels-values__values span.ux-textspans"))
-            .await
-        {
-            if let Ok(text) = color_elem.text().await {
-                item_specifics.push(format!("Color: {}", text.trim()));
+        for specific in &selectors.item.specifics {
+            if let Ok(elem) = client
+                .wait()
+                .at_most(std::time::Duration::from_secs(2))
+                .for_element(fantoccini::Locator::Css(&specific.selector))
+                .await
+                && let Ok(text) = elem.text().await
+            {
+                item_specifics.push(format!("{}: {}", specific.name, text.trim()));
             }
         }
         
@@ -1204,7 +1735,7 @@ impl App {
         let description = if let Ok(desc_iframe) = client
             .wait()
             .at_most(std::time::Duration::from_secs(3))
-            .for_element(fantoccini::Locator::Css("#desc_ifr"))
+            .for_element(fantoccini::Locator::Css(&selectors.item.description))
             .await
         {
             // Get iframe src and try to extract some basic info
@@ -1221,152 +1752,65 @@ impl App {
         Ok((item_specifics, description))
     }
     
-    
-    /// Enhanced function to scrape listings and enrich them with detailed information.
-    /// This visits each item page to get item specifics and descriptions.
-    pub async fn scrape_listings_with_details(client: &Client) -> color_eyre::Result<Vec<Listing>> {
-        info!("Starting to scrape listings with detailed information");
-        
-        // First get the basic listings
-        let mut listings = Self::scrape_active_listings(client).await?;
-        
-        let total_listings = listings.len();
-        info!("Enriching {} listings with detailed information", total_listings);
-        
-        // For each listing, scrape detailed information
-        for (index, listing) in listings.iter_mut().enumerate() {
-            info!("Processing listing {}/{}: {}", index + 1, total_listings, listing.title);
-            
-            // Construct URL from item_id
-            if let Some(item_id) = &listing.item_id {
-                let item_url = format!("https://www.ebay.com/itm/{}", item_id);
-                
-                match Self::scrape_item_details(client, &item_url).await {
-                    Ok((item_specifics, description)) => {
-                        listing.item_specifics = item_specifics;
-                        listing.description = description;
-                        info!("Successfully enriched listing: {}", listing.title);
-                    }
-                    Err(e) => {
-                        error!("Failed to scrape details for {}: {}", listing.title, e);
-                        // Continue with the next listing rather than failing completely
-                    }
-                }
-                
-                // Add a small delay between requests to be respectful
-                tokio::time::sleep(tokio::time::Duration::from_millis(1500)).await;
-            }
+    /// Returns the seller-level results of the current run.
+    pub fn run_summary(&self) -> RunSummary {
+        RunSummary {
+            seller: self.options.seller.clone(),
+            started_at: self.started_at,
+            finished_at: Utc::now(),
+            feedback_score: self.feedback_score.clone(),
+            items_sold: self.items_sold,
+            follower_count: self.follower_count,
+            partial: !self.is_complete_run(),
         }
-        
-        info!("Completed enriching listings with details");
-        Ok(listings)
     }
 
-    /// Scrapes active eBay listings from the current page and saves them to a CSV file.
-    /// This is a convenience method that combines scraping and CSV export.
-    pub async fn scrape_and_save_to_csv(client: &Client, filename: &str) -> color_eyre::Result<()> {
-        let listings = Self::scrape_active_listings(client).await?;
-        
-        if listings.is_empty() {
-            info!("No listings found to save");
-            return Ok(());
-        }
+    /// Records the finished run and its listings in the SQLite database, and compares it with
+    /// the seller's previous complete run. Partial runs are recorded but not compared.
+    pub fn record_run(&mut self) -> color_eyre::Result<i64> {
+        let summary = self.run_summary();
+        let mut store = Store::open(&self.options.database)?;
+        let run_id = store.record_run(&summary, &self.listings)?;
 
-        // Create CSV writer
-        let mut wtr = csv::Writer::from_path(filename)?;
-        
-        // Write CSV headers
-        wtr.write_record(&[
-            "title", "price", "shipping", "condition", "watchers", "seller", 
-            "seller_feedback", "buy_it_now", "accepts_offers", "location", 
-            "quantity_available", "is_new_listing", "item_id", "url", "notes",
-            "item_specifics", "description"
-        ])?;
-
-        // Write listing data
-        for listing in &listings {
-            let watchers_str = listing.watchers.map_or(String::new(), |w| w.to_string());
-            let quantity_str = listing.quantity_available.map_or(String::new(), |q| q.to_string());
-            let buy_it_now_str = listing.buy_it_now.to_string();
-            let accepts_offers_str = listing.accepts_offers.to_string();
-            let is_new_listing_str = listing.is_new_listing.to_string();
-            let notes_str = listing.notes.join("; ");
-            let item_specifics_str = listing.item_specifics.join("; ");
-            
-            wtr.write_record(&[
-                &listing.title,
-                &listing.price,
-                listing.shipping.as_deref().unwrap_or(""),
-                listing.condition.as_deref().unwrap_or(""),
-                &watchers_str,
-                listing.seller.as_deref().unwrap_or(""),
-                listing.seller_feedback.as_deref().unwrap_or(""),
-                &buy_it_now_str,
-                &accepts_offers_str,
-                listing.location.as_deref().unwrap_or(""),
-                &quantity_str,
-                &is_new_listing_str,
-                listing.item_id.as_deref().unwrap_or(""),
-                listing.url.as_deref().unwrap_or(""),
-                &notes_str,
-                &item_specifics_str,
-                listing.description.as_deref().unwrap_or(""),
-            ])?;
+        if !summary.partial
+            && let Some(&previous_run) = store.recent_runs(&self.options.seller, 2)?.get(1)
+        {
+            let previous = store.run_listings(previous_run)?;
+            let diff = ListingDiff::compare(
+                &format!("run {}", previous_run),
+                &previous,
+                &format!("run {}", run_id),
+                &self.listings,
+            );
+            info!(
+                "Compared with run {}: {} new, {} ended, {} changes",
+                previous_run,
+                diff.added.len(),
+                diff.removed.len(),
+                diff.changes.len()
+            );
+            self.diff = Some(diff);
         }
-
-        wtr.flush()?;
-        info!("Successfully saved {} listings to {}", listings.len(), filename);
-        Ok(())
+        self.price_history = PriceHistory::load(&store, &self.options.seller)?;
+        Ok(run_id)
     }
-    
-    /// Saves the currently stored listings to a CSV file.
-    pub fn save_listings_to_csv(&self, filename: &str) -> color_eyre::Result<()> {
+
+    /// Exports the currently stored listings to `path` in the configured format.
+    pub fn export_listings(&self, path: &Path) -> color_eyre::Result<()> {
         if self.listings.is_empty() {
             info!("No listings to save");
             return Ok(());
         }
 
-        let mut wtr = csv::Writer::from_path(filename)?;
-        
-        wtr.write_record(&[
-            "title", "price", "shipping", "condition", "watchers", "seller", 
-            "seller_feedback", "buy_it_now", "accepts_offers", "location", 
-            "quantity_available", "is_new_listing", "item_id", "url", "notes",
-            "item_specifics", "description"
-        ])?;
-
-        for listing in &self.listings {
-            let watchers_str = listing.watchers.map_or(String::new(), |w| w.to_string());
-            let quantity_str = listing.quantity_available.map_or(String::new(), |q| q.to_string());
-            let buy_it_now_str = listing.buy_it_now.to_string();
-            let accepts_offers_str = listing.accepts_offers.to_string();
-            let is_new_listing_str = listing.is_new_listing.to_string();
-            let notes_str = listing.notes.join("; ");
-            let item_specifics_str = listing.item_specifics.join("; ");
-            
-            wtr.write_record(&[
-                &listing.title,
-                &listing.price,
-                listing.shipping.as_deref().unwrap_or(""),
-                listing.condition.as_deref().unwrap_or(""),
-                &watchers_str,
-                listing.seller.as_deref().unwrap_or(""),
-                listing.seller_feedback.as_deref().unwrap_or(""),
-                &buy_it_now_str,
-                &accepts_offers_str,
-                listing.location.as_deref().unwrap_or(""),
-                &quantity_str,
-                &is_new_listing_str,
-                listing.item_id.as_deref().unwrap_or(""),
-                listing.url.as_deref().unwrap_or(""),
-                &notes_str,
-                &item_specifics_str,
-                listing.description.as_deref().unwrap_or(""),
-            ])?;
-        }
-
-        wtr.flush()?;
-        info!("Successfully saved {} listings to {}", self.listings.len(), filename);
+        export::export_listings(
+            path,
+            self.options.format,
+            self.options.export_columns(),
+            self.options.marketplace,
+            &self.run_summary(),
+            &self.listings,
+        )?;
+        info!("Successfully saved {} listings to {}", self.listings.len(), path.display());
         Ok(())
     }
 }
@@ -1397,12 +1841,13 @@ mod tests {
                     <span class="s-item__price">$29.99</span>
                     <span class="s-item__shipping">Free shipping</span>
                     <span class="SECONDARY_INFO">New</span>
+                    <span class="s-item__bids">3 bids</span>
                 </li>
             </ul>
         </div>
         "#;
 
-        let result = App::scrape_listings_from_html(sample_html);
+        let result = App::scrape_listings_from_html(sample_html, &SelectorRegistry::default());
         assert!(result.is_ok());
         
         let listings = result.unwrap();
@@ -1417,6 +1862,7 @@ mod tests {
         assert_eq!(first_listing.seller, Some("seller123".to_string()));
         assert_eq!(first_listing.watchers, Some(5));
         assert!(first_listing.buy_it_now);
+        assert_eq!(first_listing.format, Some(ListingFormat::BuyItNow));
         assert!(first_listing.is_new_listing);
         assert_eq!(first_listing.location, Some("From United States".to_string()));
 
@@ -1426,5 +1872,68 @@ mod tests {
         assert_eq!(second_listing.price, "$29.99");
         assert_eq!(second_listing.shipping, Some("Free shipping".to_string()));
         assert_eq!(second_listing.condition, Some("New".to_string()));
+        assert_eq!(second_listing.bids, Some(3));
+        assert_eq!(second_listing.format, Some(ListingFormat::Auction));
+    }
+
+    #[test]
+    fn test_seller_stats_stage() {
+        // A store page whose seller card changed or did not render
+        let empty = App::parse_seller_stats("<html><body><div class=\"str-seller-card\"></div></body></html>", Marketplace::Us);
+        assert_eq!(empty, SellerStats::default());
+        assert!(matches!(App::seller_stats_result(&empty), AppEvent::StageFailed(Stage::SellerStats, _)));
+
+        let html = r#"
+            <div class="str-seller-card__store-stats-content">
+                <div title="1,234 items sold"><span>1,234</span></div>
+                <div><a class="str-seller-card__feedback-link">99.5% positive feedback</a></div>
+                <div>56 followers</div>
+            </div>"#;
+        let stats = App::parse_seller_stats(html, Marketplace::Us);
+        assert_eq!(stats.items_sold, Some(1234));
+        assert_eq!(stats.feedback_score.as_deref(), Some("99.5% positive feedback"));
+        assert_eq!(stats.follower_count, Some(56));
+        assert!(matches!(
+            App::seller_stats_result(&stats),
+            AppEvent::StageFinished(Stage::SellerStats, summary) if summary == "3/3 stats found"
+        ));
+    }
+
+    #[test]
+    fn test_skips_placeholder_and_flags_sponsored() {
+        let sample_html = r#"
+        <ul>
+            <li class="s-item">
+                <div class="s-item__title">Shop on eBay</div>
+                <span class="s-item__price">$20.00</span>
+                <a class="s-item__link" href="https://www.ebay.com/itm/123456">link</a>
+            </li>
+            <li class="s-item">
+                <div class="s-item__title">Promoted Jacket</div>
+                <span class="s-item__price">$35.00</span>
+                <div class="s-item__sep"><span role="text">Sponsored</span></div>
+            </li>
+            <li class="s-item">
+                <div class="s-item__title">Plain Jacket</div>
+                <span class="s-item__price">$30.00</span>
+            </li>
+        </ul>
+        "#;
+
+        let listings = App::scrape_listings_from_html(sample_html, &SelectorRegistry::default()).unwrap();
+        let titles: Vec<_> = listings.iter().map(|listing| listing.title.as_str()).collect();
+        assert_eq!(titles, ["Promoted Jacket", "Plain Jacket"]);
+        assert!(listings[0].sponsored);
+        assert!(!listings[1].sponsored);
+    }
+
+    #[test]
+    fn test_parse_price() {
+        assert_eq!(parse_price("$19.99"), Some(19.99));
+        assert_eq!(parse_price("$1,234.50"), Some(1234.5));
+        assert_eq!(parse_price("$10.00 to $20.00"), Some(10.0));
+        assert_eq!(parse_price("Free shipping"), None);
+        assert_eq!(parse_price("EUR 1.234,50"), Some(1234.5));
+        assert_eq!(parse_price("£12.99."), Some(12.99));
     }
 }
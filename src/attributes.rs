@@ -0,0 +1,176 @@
+use crate::marketplace::Vocabulary;
+use serde::{Deserialize, Serialize};
+
+/// How an item is sold, as stated by one of the card's attribute rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ListingFormat {
+    /// Fixed price with "Buy It Now".
+    BuyItNow,
+    /// "or Best Offer" is accepted.
+    BestOffer,
+    /// Sold by auction.
+    Auction,
+}
+
+impl ListingFormat {
+    /// Name of the format in exports, e.g. `buy_it_now`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BuyItNow => "buy_it_now",
+            Self::BestOffer => "best_offer",
+            Self::Auction => "auction",
+        }
+    }
+
+    /// Reads back a name written by [`ListingFormat::as_str`].
+    pub fn parse(name: &str) -> Option<Self> {
+        [Self::BuyItNow, Self::BestOffer, Self::Auction]
+            .into_iter()
+            .find(|format| format.as_str() == name)
+    }
+}
+
+/// What one attribute row of a listing card says.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CardAttribute {
+    /// Shipping or delivery cost, e.g. `+$5.99 delivery`.
+    Shipping(String),
+    /// Where the item ships from, e.g. `Located in United States`.
+    Location(String),
+    /// How the item is sold.
+    Format(ListingFormat),
+    /// Number of bids of an auction, e.g. `5 bids · 2d 3h left`.
+    Bids(u32),
+    /// Number of watchers, e.g. `12 watchers`.
+    Watchers(u32),
+    /// The "New Listing" badge.
+    NewListing,
+    /// A "Sponsored" or "Promoted" label of a paid placement.
+    Sponsored,
+    /// Anything else, e.g. `Free returns`.
+    Other(String),
+}
+
+impl CardAttribute {
+    /// Classifies the text of one attribute row by its wording in the marketplace's language.
+    ///
+    /// Counts are checked first, since a row like `3 bids · Free delivery` is about the auction,
+    /// and phrases are matched case-insensitively so both card layouts are understood.
+    pub fn classify(text: &str, vocabulary: &Vocabulary) -> Self {
+        let text = text.trim();
+        let lower = text.to_lowercase();
+        let contains = |phrases: &[&str]| phrases.iter().any(|phrase| lower.contains(phrase));
+        let is = |phrases: &[&str]| phrases.contains(&lower.as_str());
+        if contains(vocabulary.bids)
+            && let Some(count) = leading_count(&lower)
+        {
+            return Self::Bids(count);
+        }
+        if contains(vocabulary.watchers)
+            && let Some(count) = leading_count(&lower)
+        {
+            return Self::Watchers(count);
+        }
+        if vocabulary.location.iter().any(|prefix| lower.starts_with(prefix)) {
+            return Self::Location(text.to_string());
+        }
+        if contains(vocabulary.shipping) {
+            return Self::Shipping(text.to_string());
+        }
+        if contains(vocabulary.best_offer) {
+            return Self::Format(ListingFormat::BestOffer);
+        }
+        if contains(vocabulary.buy_it_now) {
+            return Self::Format(ListingFormat::BuyItNow);
+        }
+        if is(vocabulary.auction) {
+            return Self::Format(ListingFormat::Auction);
+        }
+        if is(vocabulary.new_listing) {
+            return Self::NewListing;
+        }
+        if is(vocabulary.sponsored) {
+            return Self::Sponsored;
+        }
+        Self::Other(text.to_string())
+    }
+}
+
+/// Returns the first number in `text`, ignoring thousands separators, e.g. 1234 for
+/// `1,234 watchers`.
+pub fn leading_count(text: &str) -> Option<u32> {
+    let start = text.find(|c: char| c.is_ascii_digit())?;
+    let digits: String = text[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == ',')
+        .filter(char::is_ascii_digit)
+        .collect();
+    digits.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::marketplace::Marketplace;
+    use scraper::{Html, Selector};
+
+    #[test]
+    fn test_classify_su_card_rows() {
+        // Attribute rows of a captured su-card, in page order
+        let html = r#"
+            <div class="su-card-container">
+                <div class="su-card-container__attributes__primary">
+                    <div class="s-card__attribute-row"><span class="su-styled-text primary bold large-1 s-card__price">$45.00</span></div>
+                    <div class="s-card__attribute-row"><span class="su-styled-text secondary large">5 bids</span><span class="su-styled-text secondary large"> · </span><span class="su-styled-text secondary large">2d 3h left</span></div>
+                    <div class="s-card__attribute-row"><span class="su-styled-text secondary large">or Best Offer</span></div>
+                    <div class="s-card__attribute-row"><span class="su-styled-text secondary large">+$6.30 delivery</span></div>
+                    <div class="s-card__attribute-row"><span class="su-styled-text secondary large">Located in United States</span></div>
+                    <div class="s-card__attribute-row"><span class="su-styled-text secondary large">1,204 watchers</span></div>
+                    <div class="s-card__attribute-row"><span class="su-styled-text positive bold large">Free returns</span></div>
+                </div>
+            </div>"#;
+        let document = Html::parse_document(html);
+        let selector = Selector::parse(".s-card__attribute-row").unwrap();
+        let attributes: Vec<_> = document
+            .select(&selector)
+            .map(|row| CardAttribute::classify(&row.text().collect::<String>(), Marketplace::Us.vocabulary()))
+            .collect();
+
+        assert_eq!(
+            attributes,
+            vec![
+                CardAttribute::Other("$45.00".to_string()),
+                CardAttribute::Bids(5),
+                CardAttribute::Format(ListingFormat::BestOffer),
+                CardAttribute::Shipping("+$6.30 delivery".to_string()),
+                CardAttribute::Location("Located in United States".to_string()),
+                CardAttribute::Watchers(1204),
+                CardAttribute::Other("Free returns".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_classify_legacy_rows() {
+        let classify = |text| CardAttribute::classify(text, Marketplace::Us.vocabulary());
+        assert_eq!(classify("Free shipping"), CardAttribute::Shipping("Free shipping".to_string()));
+        assert_eq!(
+            classify("From United States"),
+            CardAttribute::Location("From United States".to_string())
+        );
+        assert_eq!(classify("Buy It Now"), CardAttribute::Format(ListingFormat::BuyItNow));
+        assert_eq!(classify("0 bids · 6h 12m left"), CardAttribute::Bids(0));
+        assert_eq!(classify("New Listing"), CardAttribute::NewListing);
+        assert_eq!(classify(" Sponsored "), CardAttribute::Sponsored);
+        // Without a count a mention of bidding says nothing about the number of bids
+        assert_eq!(classify("Place bid"), CardAttribute::Other("Place bid".to_string()));
+
+        let german = |text| CardAttribute::classify(text, Marketplace::De.vocabulary());
+        assert_eq!(german("EUR 4,99 Versand"), CardAttribute::Shipping("EUR 4,99 Versand".to_string()));
+        assert_eq!(german("aus Deutschland"), CardAttribute::Location("aus Deutschland".to_string()));
+        assert_eq!(german("oder Preisvorschlag"), CardAttribute::Format(ListingFormat::BestOffer));
+        assert_eq!(german("3 Gebote · Noch 1T 2Std"), CardAttribute::Bids(3));
+        assert_eq!(german("12 Beobachter"), CardAttribute::Watchers(12));
+    }
+}
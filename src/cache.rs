@@ -0,0 +1,134 @@
+use crate::app::Listing;
+use chrono::{DateTime, TimeDelta, Utc};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Enriched details of one item as they were when its page was last visited.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    /// Card title at the time of enrichment.
+    pub title: String,
+    /// Card price at the time of enrichment.
+    pub price: String,
+    /// Item specifics scraped from the item page.
+    pub item_specifics: Vec<String>,
+    /// Description scraped from the item page.
+    pub description: Option<String>,
+    /// When the item page was visited.
+    pub enriched_at: DateTime<Utc>,
+}
+
+/// Local cache of enriched item details keyed by `item_id`, used to skip unchanged items.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct EnrichmentCache {
+    entries: HashMap<String, CacheEntry>,
+}
+
+impl EnrichmentCache {
+    /// Returns the file the cache is stored in.
+    pub fn path() -> PathBuf {
+        PathBuf::from(".ebay_enrichment_cache.json")
+    }
+
+    /// Loads the cache from `path`, starting empty when the file does not exist yet.
+    pub fn load(path: &Path) -> color_eyre::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let cache: Self = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        info!("Loaded {} cached items from {}", cache.entries.len(), path.display());
+        Ok(cache)
+    }
+
+    /// Writes the cache to `path` via a temporary file.
+    pub fn save(&self, path: &Path) -> color_eyre::Result<()> {
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, serde_json::to_vec(self)?)?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Returns the cached entry for `listing` if it is younger than `ttl` and the card's title
+    /// and price have not changed since it was enriched.
+    pub fn fresh_entry(&self, listing: &Listing, ttl: TimeDelta, now: DateTime<Utc>) -> Option<&CacheEntry> {
+        let entry = self.entries.get(listing.item_id.as_ref()?)?;
+        let unchanged = entry.title == listing.title && entry.price == listing.price;
+        (unchanged && now - entry.enriched_at < ttl).then_some(entry)
+    }
+
+    /// Fills in cached details for every listing with a fresh entry and returns their item IDs,
+    /// which do not need to be revisited.
+    pub fn apply(&self, listings: &mut [Listing], ttl: TimeDelta) -> BTreeSet<String> {
+        let now = Utc::now();
+        let mut cached = BTreeSet::new();
+        for listing in listings.iter_mut() {
+            if let Some(entry) = self.fresh_entry(listing, ttl, now) {
+                listing.item_specifics = entry.item_specifics.clone();
+                listing.description = entry.description.clone();
+                if let Some(item_id) = &listing.item_id {
+                    cached.insert(item_id.clone());
+                }
+            }
+        }
+        info!("{} of {} listings served from the enrichment cache", cached.len(), listings.len());
+        cached
+    }
+
+    /// Records the details of a freshly enriched listing. Listings without any details are not
+    /// cached, so their item page is visited again on the next run.
+    pub fn insert(&mut self, listing: &Listing) {
+        if listing.item_specifics.is_empty() && listing.description.is_none() {
+            return;
+        }
+        if let Some(item_id) = &listing.item_id {
+            self.entries.insert(
+                item_id.clone(),
+                CacheEntry {
+                    title: listing.title.clone(),
+                    price: listing.price.clone(),
+                    item_specifics: listing.item_specifics.clone(),
+                    description: listing.description.clone(),
+                    enriched_at: Utc::now(),
+                },
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fresh_entry_rules() {
+        let mut listing = Listing {
+            title: "Sample Item".to_string(),
+            price: "$10.00".to_string(),
+            item_id: Some("1234567890".to_string()),
+            item_specifics: vec!["Brand: Acme".to_string()],
+            ..Listing::default()
+        };
+        let mut cache = EnrichmentCache::default();
+        cache.insert(&listing);
+        let ttl = TimeDelta::hours(24);
+        let now = Utc::now();
+
+        assert!(cache.fresh_entry(&listing, ttl, now).is_some());
+        assert!(cache.fresh_entry(&listing, ttl, now + TimeDelta::hours(25)).is_none());
+
+        listing.price = "$8.00".to_string();
+        assert!(cache.fresh_entry(&listing, ttl, now).is_none());
+
+        listing.item_id = Some("999".to_string());
+        assert!(cache.fresh_entry(&listing, ttl, now).is_none());
+
+        let mut cache = EnrichmentCache::default();
+        cache.insert(&Listing {
+            item_id: Some("1234567890".to_string()),
+            ..Listing::default()
+        });
+        assert!(cache.entries.is_empty());
+    }
+}
@@ -0,0 +1,268 @@
+use crate::event::{AppEvent, Event};
+use fantoccini::Client;
+use log::{error, info};
+use scraper::{Html, Selector};
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, mpsc};
+
+/// Kind of bot challenge eBay served instead of the requested page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChallengeKind {
+    /// An hCaptcha, reCAPTCHA or eBay's own CAPTCHA form.
+    Captcha,
+    /// The "Pardon our interruption" interstitial shown while eBay checks the browser.
+    Interstitial,
+    /// An access denied or blocked page.
+    AccessDenied,
+}
+
+impl fmt::Display for ChallengeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Captcha => "CAPTCHA",
+            Self::Interstitial => "bot check interstitial",
+            Self::AccessDenied => "access denied page",
+        })
+    }
+}
+
+/// Elements that only appear on challenge pages.
+const CAPTCHA_SELECTORS: [&str; 8] = [
+    "iframe[src*='hcaptcha']",
+    "iframe[src*='recaptcha']",
+    "div.h-captcha",
+    "div.g-recaptcha",
+    "#captcha_form",
+    "form[action*='captcha']",
+    "#px-captcha",
+    "#s0-0-captcha",
+];
+
+/// Lowercase title fragments of interstitial pages.
+const INTERSTITIAL_TITLES: [&str; 3] = ["pardon our interruption", "security measure", "just a moment"];
+
+/// Lowercase title or heading fragments of block pages.
+const ACCESS_DENIED_TEXTS: [&str; 3] = ["access denied", "access to this page has been denied", "you have been blocked"];
+
+/// Error returned by scraping steps that hit a challenge page instead of the expected content.
+#[derive(Debug, Clone)]
+pub struct CaptchaDetected {
+    /// Kind of challenge on the page.
+    pub kind: ChallengeKind,
+    /// URL the challenge was served at.
+    pub url: String,
+}
+
+impl fmt::Display for CaptchaDetected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} detected at {}", self.kind, self.url)
+    }
+}
+
+impl std::error::Error for CaptchaDetected {}
+
+/// A challenge currently waiting for the user.
+#[derive(Debug, Clone)]
+pub struct ActiveChallenge {
+    /// The detected challenge.
+    pub challenge: CaptchaDetected,
+    /// When the pipeline was paused for it.
+    pub since: Instant,
+}
+
+/// Error returned by every step once a challenge went unsolved for longer than the timeout.
+#[derive(Debug, Clone)]
+pub struct CaptchaTimedOut;
+
+impl fmt::Display for CaptchaTimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("scrape aborted because a challenge was not solved in time")
+    }
+}
+
+impl std::error::Error for CaptchaTimedOut {}
+
+/// Returns the challenge `error` was caused by, if any.
+pub fn challenge_of(error: &color_eyre::Report) -> Option<&CaptchaDetected> {
+    error.downcast_ref::<CaptchaDetected>()
+}
+
+/// Inspects a page for known challenge markers: the URL, the title and challenge widgets in
+/// the DOM. Challenges are often served at the original URL, so the URL alone is not enough.
+pub fn detect_challenge(url: &str, html: &str) -> Option<ChallengeKind> {
+    let document = Html::parse_document(html);
+    let title = Selector::parse("title")
+        .ok()
+        .and_then(|selector| document.select(&selector).next())
+        .map(|title| title.text().collect::<String>().to_lowercase())
+        .unwrap_or_default();
+    let heading = Selector::parse("h1")
+        .ok()
+        .and_then(|selector| document.select(&selector).next())
+        .map(|heading| heading.text().collect::<String>().to_lowercase())
+        .unwrap_or_default();
+
+    if ACCESS_DENIED_TEXTS
+        .iter()
+        .any(|text| title.contains(text) || heading.contains(text))
+    {
+        return Some(ChallengeKind::AccessDenied);
+    }
+    if INTERSTITIAL_TITLES.iter().any(|text| title.contains(text)) {
+        return Some(ChallengeKind::Interstitial);
+    }
+    let has_widget = CAPTCHA_SELECTORS.iter().any(|selector| {
+        Selector::parse(selector).is_ok_and(|selector| document.select(&selector).next().is_some())
+    });
+    if has_widget || url.to_lowercase().contains("captcha") {
+        return Some(ChallengeKind::Captcha);
+    }
+    None
+}
+
+/// Checks the page currently loaded in `client` and fails with [`CaptchaDetected`] if it is a
+/// challenge. Run this before parsing the result of every navigation.
+pub async fn check_page(client: &Client) -> color_eyre::Result<()> {
+    let url = client.current_url().await?.to_string();
+    let html = client.source().await?;
+    check_html(&url, &html)
+}
+
+/// Same as [`check_page`] for a page source that has already been fetched.
+pub fn check_html(url: &str, html: &str) -> color_eyre::Result<()> {
+    match detect_challenge(url, html) {
+        Some(kind) => {
+            info!("🔍 {} detected at {}", kind, url);
+            Err(CaptchaDetected {
+                kind,
+                url: url.to_string(),
+            }
+            .into())
+        }
+        None => Ok(()),
+    }
+}
+
+/// How often a challenge page is checked while waiting for the user to solve it.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Pauses the scrape pipeline while the user solves a challenge.
+///
+/// Every step that navigates runs through [`CaptchaGate::guard`]. When a step hits a challenge,
+/// the gate brings that browser window to the front, waits until the page is clear and then
+/// runs the same step again. While one step waits, every other step blocks before starting, so
+/// parallel workers do not keep hammering eBay and challenges are solved one window at a time.
+///
+/// If a challenge is not solved within the timeout, the gate aborts: the waiting step and every
+/// step after it fail with [`CaptchaTimedOut`], so the pipeline winds down with what it has.
+#[derive(Debug)]
+pub struct CaptchaGate {
+    lock: Mutex<()>,
+    sender: mpsc::UnboundedSender<Event>,
+    timeout: Option<Duration>,
+    aborted: AtomicBool,
+}
+
+impl CaptchaGate {
+    /// Creates a gate reporting to the app through `sender` that gives up after `timeout`, or
+    /// waits forever without one.
+    pub fn new(sender: mpsc::UnboundedSender<Event>, timeout: Option<Duration>) -> Self {
+        Self {
+            lock: Mutex::new(()),
+            sender,
+            timeout,
+            aborted: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns true once a challenge timed out and the scrape was aborted.
+    pub fn is_aborted(&self) -> bool {
+        self.aborted.load(Ordering::SeqCst)
+    }
+
+    /// Runs `step` on `client`, pausing for the user and retrying it whenever it fails with
+    /// [`CaptchaDetected`]. Any other result is returned as is.
+    pub async fn guard<T, F, Fut>(&self, client: &Client, mut step: F) -> color_eyre::Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = color_eyre::Result<T>>,
+    {
+        loop {
+            // Wait out a challenge another step is currently solving
+            drop(self.lock.lock().await);
+            if self.is_aborted() {
+                return Err(CaptchaTimedOut.into());
+            }
+
+            let error = match step().await {
+                Ok(value) => return Ok(value),
+                Err(error) => error,
+            };
+            let Some(challenge) = challenge_of(&error).cloned() else {
+                return Err(error);
+            };
+
+            let _paused = self.lock.lock().await;
+            self.wait_until_solved(client, challenge).await?;
+        }
+    }
+
+    /// Shows the challenge to the user and polls the page until it is gone.
+    async fn wait_until_solved(&self, client: &Client, challenge: CaptchaDetected) -> color_eyre::Result<()> {
+        let _ = self.sender.send(Event::App(AppEvent::CaptchaDetected(challenge)));
+        if let Err(e) = client.maximize_window().await {
+            error!("Failed to bring the browser to the foreground: {}", e);
+        }
+
+        let started = Instant::now();
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            if let Some(timeout) = self.timeout
+                && started.elapsed() >= timeout
+            {
+                error!("Challenge not solved within {}s - aborting", timeout.as_secs());
+                self.aborted.store(true, Ordering::SeqCst);
+                let _ = self.sender.send(Event::App(AppEvent::CaptchaTimedOut));
+                return Err(CaptchaTimedOut.into());
+            }
+            let url = client.current_url().await?;
+            let html = client.source().await?;
+            if detect_challenge(url.as_str(), &html).is_none() {
+                break;
+            }
+        }
+
+        info!("✅ Challenge solved - continuing");
+        let _ = client.minimize_window().await;
+        let _ = self.sender.send(Event::App(AppEvent::CaptchaResolved));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_challenge() {
+        let interstitial = "<html><head><title>Pardon Our Interruption...</title></head><body></body></html>";
+        assert_eq!(
+            detect_challenge("https://www.ebay.com/usr/seller", interstitial),
+            Some(ChallengeKind::Interstitial)
+        );
+
+        let hcaptcha = r#"<html><body><iframe src="https://newassets.hcaptcha.com/captcha/v1"></iframe></body></html>"#;
+        assert_eq!(detect_challenge("https://www.ebay.com/itm/1", hcaptcha), Some(ChallengeKind::Captcha));
+
+        let denied = "<html><head><title>eBay</title></head><body><h1>Access Denied</h1></body></html>";
+        assert_eq!(detect_challenge("https://www.ebay.com/itm/1", denied), Some(ChallengeKind::AccessDenied));
+
+        let item = "<html><head><title>Levi's 505 Jeans | eBay</title></head><body><h1>Levi's</h1></body></html>";
+        assert_eq!(detect_challenge("https://www.ebay.com/itm/1", item), None);
+        assert!(check_html("https://www.ebay.com/splashui/captcha?ap=1", item)
+            .is_err_and(|e| challenge_of(&e).is_some()));
+    }
+}
@@ -0,0 +1,122 @@
+use crate::app::Listing;
+use chrono::{DateTime, Utc};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// Furthest point a checkpointed scrape has reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CheckpointStage {
+    /// The store's listing page has been scraped; no item has been enriched yet.
+    ListingsScraped,
+    /// Item pages are being visited for details.
+    Enriching,
+}
+
+/// On-disk snapshot of an unfinished scrape, written after every page and enriched item.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Seller whose store is being scraped.
+    pub seller: String,
+    /// Stage the scrape had reached.
+    pub stage: CheckpointStage,
+    /// When the checkpoint was last written.
+    pub updated_at: DateTime<Utc>,
+    /// Seller feedback score scraped from the store page.
+    pub feedback_score: Option<String>,
+    /// Seller items sold count scraped from the store page.
+    pub items_sold: Option<u32>,
+    /// Seller follower count scraped from the store page.
+    pub follower_count: Option<u32>,
+    /// Listings scraped so far, including the details of enriched ones.
+    pub listings: Vec<Listing>,
+    /// Item IDs of listings that have already been enriched.
+    pub enriched_item_ids: BTreeSet<String>,
+}
+
+impl Checkpoint {
+    /// Creates a checkpoint for freshly scraped listings.
+    pub fn new(seller: &str, listings: Vec<Listing>) -> Self {
+        Self {
+            seller: seller.to_string(),
+            stage: CheckpointStage::ListingsScraped,
+            updated_at: Utc::now(),
+            feedback_score: None,
+            items_sold: None,
+            follower_count: None,
+            listings,
+            enriched_item_ids: BTreeSet::new(),
+        }
+    }
+
+    /// Returns the checkpoint file used for `seller`.
+    pub fn path_for(seller: &str) -> PathBuf {
+        PathBuf::from(format!(".ebay_checkpoint_{}.json", seller))
+    }
+
+    /// Loads the checkpoint at `path`, returning `None` when there is none.
+    pub fn load(path: &Path) -> color_eyre::Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let contents = std::fs::read_to_string(path)?;
+        let checkpoint = serde_json::from_str(&contents)?;
+        info!("Loaded checkpoint from {}", path.display());
+        Ok(Some(checkpoint))
+    }
+
+    /// Writes the checkpoint to `path` via a temporary file so a crash never leaves it half-written.
+    pub fn save(&mut self, path: &Path) -> color_eyre::Result<()> {
+        self.updated_at = Utc::now();
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Deletes the checkpoint at `path` once the scrape has finished.
+    pub fn remove(path: &Path) -> color_eyre::Result<()> {
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    /// Records the enriched version of the listing at `index`.
+    pub fn mark_enriched(&mut self, index: usize, listing: Listing) {
+        self.stage = CheckpointStage::Enriching;
+        if let Some(item_id) = &listing.item_id {
+            self.enriched_item_ids.insert(item_id.clone());
+        }
+        if let Some(slot) = self.listings.get_mut(index) {
+            *slot = listing;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mark_enriched_round_trip() {
+        let listing = Listing {
+            title: "Sample Item".to_string(),
+            price: "$10.00".to_string(),
+            item_id: Some("1234567890".to_string()),
+            ..Listing::default()
+        };
+        let mut checkpoint = Checkpoint::new("seller123", vec![listing.clone()]);
+
+        let mut enriched = listing;
+        enriched.item_specifics = vec!["Brand: Acme".to_string()];
+        checkpoint.mark_enriched(0, enriched);
+
+        let json = serde_json::to_string(&checkpoint).unwrap();
+        let restored: Checkpoint = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.stage, CheckpointStage::Enriching);
+        assert!(restored.enriched_item_ids.contains("1234567890"));
+        assert_eq!(restored.listings[0].item_specifics, vec!["Brand: Acme".to_string()]);
+    }
+}
@@ -0,0 +1,220 @@
+use crate::diff::DiffSource;
+use crate::export::{self, Column, ExportFormat};
+use crate::marketplace::Marketplace;
+use crate::rate_limit::{QuietHours, RateLimitConfig};
+use chrono::{TimeDelta, Utc};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Scrapes an eBay seller's store and shows the results in a terminal dashboard.
+#[derive(Debug, Parser)]
+#[command(version)]
+pub struct Cli {
+    /// What to do instead of scraping.
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Options for the scrape run.
+    #[command(flatten)]
+    pub scrape: ScrapeOptions,
+}
+
+/// Commands that work on previously scraped results instead of scraping.
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Show what changed between two scrape results.
+    Diff(DiffArgs),
+    /// Export the price, watcher and quantity history of the seller's items.
+    History(HistoryArgs),
+    /// Browse a previously exported CSV, JSON or JSON Lines file without scraping.
+    View(ViewArgs),
+    /// Work with the CSS selector definitions.
+    #[command(subcommand)]
+    Selectors(SelectorsCommand),
+}
+
+/// Subcommands of the `selectors` command.
+#[derive(Debug, Subcommand)]
+pub enum SelectorsCommand {
+    /// Run every selector against a saved store or item page and report what each one matched.
+    Check(SelectorsCheckArgs),
+}
+
+/// Arguments of the `selectors check` command.
+#[derive(Debug, Args)]
+pub struct SelectorsCheckArgs {
+    /// Saved HTML page to check, e.g. from the browser's "Save Page As".
+    pub page: PathBuf,
+}
+
+/// Arguments of the `diff` command.
+#[derive(Debug, Args)]
+pub struct DiffArgs {
+    /// Older result: an exported file or `run:<ID>`. Defaults to the run before the newer one.
+    pub old: Option<DiffSource>,
+    /// Newer result: an exported file or `run:<ID>`. Defaults to the seller's latest run.
+    pub new: Option<DiffSource>,
+    /// Also write the changes to this CSV report.
+    #[arg(long)]
+    pub report: Option<PathBuf>,
+}
+
+/// Arguments of the `history` command.
+#[derive(Debug, Args)]
+pub struct HistoryArgs {
+    /// File the history is written to, as CSV or as Parquet when it ends in `.parquet`.
+    #[arg(long, default_value = "price_history.csv")]
+    pub output: PathBuf,
+}
+
+/// Arguments of the `view` command.
+#[derive(Debug, Args)]
+pub struct ViewArgs {
+    /// Exported file to open.
+    pub file: PathBuf,
+}
+
+/// Per-run options controlling how the scraper talks to eBay.
+#[derive(Debug, Clone, Args)]
+pub struct ScrapeOptions {
+    /// eBay username of the seller whose store is scraped.
+    #[arg(long, global = true, default_value = "thriftngo5")]
+    pub seller: String,
+    /// eBay site the store is on. Decides the URLs, the expected currency and the wording
+    /// matched on the pages.
+    #[arg(long, global = true, value_enum, default_value_t = Marketplace::Us)]
+    pub marketplace: Marketplace,
+    /// Continue the last unfinished scrape of this seller from its checkpoint.
+    #[arg(long)]
+    pub resume: bool,
+    /// Maximum sustained number of page navigations per minute.
+    #[arg(long, default_value_t = 20)]
+    pub requests_per_minute: u32,
+    /// Upper bound of the random delay added to every navigation, in milliseconds.
+    #[arg(long, default_value_t = 1500)]
+    pub jitter_ms: u64,
+    /// Number of navigations allowed back to back before throttling kicks in.
+    #[arg(long, default_value_t = 3)]
+    pub burst: u32,
+    /// Local hours during which no navigation happens, e.g. `23-7`.
+    #[arg(long, value_name = "START-END")]
+    pub quiet_hours: Option<QuietHours>,
+    /// Drop sponsored and promoted listings instead of only flagging them.
+    #[arg(long)]
+    pub exclude_sponsored: bool,
+    /// Number of browser sessions used to enrich listings in parallel.
+    #[arg(long, default_value_t = 1)]
+    pub workers: usize,
+    /// Hours after which cached item details are considered stale and the item is revisited.
+    #[arg(long, default_value_t = 168)]
+    pub cache_ttl_hours: u32,
+    /// Seconds to wait for a CAPTCHA to be solved before aborting the scrape. 0 waits forever.
+    #[arg(long, default_value_t = 900)]
+    pub captcha_timeout_secs: u64,
+    /// Shell command run when a CAPTCHA needs solving, e.g. `notify-send eBay "$EBAY_CHALLENGE"`.
+    /// The challenge and its URL are passed in `EBAY_CHALLENGE` and `EBAY_CHALLENGE_URL`.
+    #[arg(long)]
+    pub notify_command: Option<String>,
+    /// Where the results of a run are written.
+    #[arg(long, value_enum, default_value_t = Storage::Both)]
+    pub storage: Storage,
+    /// File the listings are exported to. Overrides `--output-dir` and `--file-name-template`.
+    #[arg(long)]
+    pub output: Option<PathBuf>,
+    /// Directory exports are written to.
+    #[arg(long, default_value = "exports")]
+    pub output_dir: PathBuf,
+    /// Name of the export file. Variables: `{seller}`, `{date}`, `{time}`, `{format}`.
+    #[arg(
+        long,
+        default_value = "ebay_listings_{seller}_{date}_{time}.{format}",
+        value_parser = export::parse_file_name_template
+    )]
+    pub file_name_template: String,
+    /// Export format. Defaults to the extension of `--output`, or CSV.
+    #[arg(long, value_enum)]
+    pub format: Option<ExportFormat>,
+    /// Comma separated columns of CSV, JSON and XLSX exports, in order. Defaults to every column.
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub columns: Vec<Column>,
+    /// JSON file with the CSS selectors of listing cards and item pages. The built-in selectors
+    /// are used when the file does not exist.
+    #[arg(long, global = true, default_value = "selectors.json")]
+    pub selectors: PathBuf,
+    /// SQLite database keeping the history of every run.
+    #[arg(long, global = true, default_value = "ebay.db")]
+    pub database: PathBuf,
+}
+
+/// Destinations for the results of a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Storage {
+    /// Only write an export file.
+    File,
+    /// Only record the run in the SQLite database.
+    Sqlite,
+    /// Write an export file and record the run in the database.
+    Both,
+}
+
+impl Storage {
+    /// Whether an export file is written.
+    pub fn writes_file(self) -> bool {
+        matches!(self, Self::File | Self::Both)
+    }
+
+    /// Whether the run is recorded in the database.
+    pub fn writes_database(self) -> bool {
+        matches!(self, Self::Sqlite | Self::Both)
+    }
+}
+
+impl Default for ScrapeOptions {
+    fn default() -> Self {
+        Cli::parse_from(["ebay"]).scrape
+    }
+}
+
+impl ScrapeOptions {
+    /// Returns how long cached item details stay valid.
+    pub fn cache_ttl(&self) -> TimeDelta {
+        TimeDelta::hours(self.cache_ttl_hours.into())
+    }
+
+    /// Builds the rate limiter settings for this run.
+    pub fn rate_limit_config(&self) -> RateLimitConfig {
+        RateLimitConfig {
+            requests_per_minute: self.requests_per_minute,
+            jitter: Duration::from_millis(self.jitter_ms),
+            burst: self.burst,
+            quiet_hours: self.quiet_hours,
+        }
+    }
+
+    /// Returns how long to wait for a CAPTCHA to be solved, if there is a limit.
+    pub fn captcha_timeout(&self) -> Option<Duration> {
+        (self.captcha_timeout_secs > 0).then(|| Duration::from_secs(self.captcha_timeout_secs))
+    }
+
+    /// Returns the columns of CSV, JSON and XLSX exports, in order.
+    pub fn export_columns(&self) -> &[Column] {
+        if self.columns.is_empty() {
+            &Column::ALL
+        } else {
+            &self.columns
+        }
+    }
+
+    /// Returns the file this run's listings are exported to.
+    pub fn export_path(&self) -> PathBuf {
+        match &self.output {
+            Some(output) => output.clone(),
+            None => self.output_dir.join(export::render_file_name(
+                &self.file_name_template,
+                &self.seller,
+                Utc::now(),
+                self.format.unwrap_or_default(),
+            )),
+        }
+    }
+}
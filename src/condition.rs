@@ -0,0 +1,202 @@
+use crate::app::Listing;
+use std::fmt;
+
+/// eBay's standard item conditions. Sellers and card layouts word them differently, e.g.
+/// "Pre-Owned", "Used" and "Gebraucht" are all [`Condition::Used`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Condition {
+    /// Brand new and unused.
+    New,
+    /// New with the original tags attached.
+    NewWithTags,
+    /// New without tags or packaging, or with defects.
+    NewOther,
+    /// Opened but unused.
+    OpenBox,
+    /// Refurbished by the manufacturer or an approved vendor.
+    CertifiedRefurbished,
+    /// Refurbished, like new.
+    ExcellentRefurbished,
+    /// Refurbished, minimal wear.
+    VeryGoodRefurbished,
+    /// Refurbished, moderate wear.
+    GoodRefurbished,
+    /// Refurbished by the seller.
+    SellerRefurbished,
+    /// Used or pre-owned.
+    Used,
+    /// For parts or not working.
+    ForParts,
+}
+
+impl Condition {
+    /// Every condition, from best to worst.
+    pub const ALL: [Condition; 11] = [
+        Condition::New,
+        Condition::NewWithTags,
+        Condition::NewOther,
+        Condition::OpenBox,
+        Condition::CertifiedRefurbished,
+        Condition::ExcellentRefurbished,
+        Condition::VeryGoodRefurbished,
+        Condition::GoodRefurbished,
+        Condition::SellerRefurbished,
+        Condition::Used,
+        Condition::ForParts,
+    ];
+
+    /// Maps condition text from a card or item page to a standard condition. Only the part before
+    /// a `·` separator is used, since newer cards append the brand and size, e.g.
+    /// `Pre-Owned · Levi's · 32 in`. Returns `None` for text that names no known condition.
+    pub fn parse(text: &str) -> Option<Self> {
+        // "Unused" would otherwise read as used
+        let text = text.split('·').next()?.trim().to_lowercase().replace("unused", "");
+        let text = text.as_str();
+        let has = |phrases: &[&str]| phrases.iter().any(|phrase| text.contains(phrase));
+
+        // Most specific wording first: "New with tags" also contains "new"
+        if has(&["for parts", "not working", "ersatzteil", "defekt"]) {
+            Some(Self::ForParts)
+        } else if has(&["certified", "manufacturer refurbished", "zertifiziert"]) {
+            Some(Self::CertifiedRefurbished)
+        } else if has(&["very good - refurbished", "very good refurbished"]) {
+            Some(Self::VeryGoodRefurbished)
+        } else if has(&["excellent - refurbished", "excellent refurbished"]) {
+            Some(Self::ExcellentRefurbished)
+        } else if has(&["good - refurbished", "good refurbished"]) {
+            Some(Self::GoodRefurbished)
+        } else if has(&["refurbished", "generalüberholt"]) {
+            Some(Self::SellerRefurbished)
+        } else if has(&["open box", "geöffnet"]) {
+            Some(Self::OpenBox)
+        } else if has(&["pre-owned", "preowned", "used", "gebraucht"]) {
+            Some(Self::Used)
+        } else if has(&["new with tags", "nwt", "mit etikett"]) {
+            Some(Self::NewWithTags)
+        } else if has(&[
+            "new other",
+            "new (other)",
+            "new without",
+            "new with box",
+            "new with defects",
+            "ohne etikett",
+            "neu: sonstige",
+            "neu – sonstige",
+        ]) {
+            Some(Self::NewOther)
+        } else if has(&["new", "neu"]) {
+            Some(Self::New)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::New => "New",
+            Self::NewWithTags => "New with tags",
+            Self::NewOther => "New other",
+            Self::OpenBox => "Open box",
+            Self::CertifiedRefurbished => "Certified refurbished",
+            Self::ExcellentRefurbished => "Excellent refurbished",
+            Self::VeryGoodRefurbished => "Very good refurbished",
+            Self::GoodRefurbished => "Good refurbished",
+            Self::SellerRefurbished => "Seller refurbished",
+            Self::Used => "Used",
+            Self::ForParts => "For parts",
+        })
+    }
+}
+
+/// Number of listings and their average price for one condition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConditionStats {
+    /// The condition, or `None` for listings without a recognized condition.
+    pub condition: Option<Condition>,
+    /// Number of listings in this condition.
+    pub count: usize,
+    /// Average parsed price of those listings, if any price could be parsed.
+    pub average_price: Option<f64>,
+}
+
+/// Groups `listings` by standard condition, from best to worst with unrecognized ones last.
+pub fn summarize(listings: &[Listing]) -> Vec<ConditionStats> {
+    Condition::ALL
+        .into_iter()
+        .map(Some)
+        .chain(std::iter::once(None))
+        .filter_map(|condition| {
+            let matching: Vec<_> = listings
+                .iter()
+                .filter(|listing| listing.condition_category() == condition)
+                .collect();
+            if matching.is_empty() {
+                return None;
+            }
+            let prices: Vec<f64> = matching.iter().filter_map(|listing| listing.price_value()).collect();
+            Some(ConditionStats {
+                condition,
+                count: matching.len(),
+                average_price: (!prices.is_empty()).then(|| prices.iter().sum::<f64>() / prices.len() as f64),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_condition_wording() {
+        let cases = [
+            ("Brand New", Some(Condition::New)),
+            ("New, unused", Some(Condition::New)),
+            ("New", Some(Condition::New)),
+            ("New with tags", Some(Condition::NewWithTags)),
+            ("New without tags", Some(Condition::NewOther)),
+            ("New (Other)", Some(Condition::NewOther)),
+            ("Open box", Some(Condition::OpenBox)),
+            ("Certified - Refurbished", Some(Condition::CertifiedRefurbished)),
+            ("Very Good - Refurbished", Some(Condition::VeryGoodRefurbished)),
+            ("Good - Refurbished", Some(Condition::GoodRefurbished)),
+            ("Seller refurbished", Some(Condition::SellerRefurbished)),
+            ("Pre-Owned · Levi's · 32 in", Some(Condition::Used)),
+            ("Used", Some(Condition::Used)),
+            ("For parts or not working", Some(Condition::ForParts)),
+            ("Gebraucht", Some(Condition::Used)),
+            ("Neu mit Etikett", Some(Condition::NewWithTags)),
+            ("Levi's", None),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(Condition::parse(text), expected, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_summarize_by_condition() {
+        let listing = |condition: &str, price: &str| Listing {
+            condition: Some(condition.to_string()),
+            price: price.to_string(),
+            ..Listing::default()
+        };
+        let listings = [
+            listing("Pre-Owned", "$10.00"),
+            listing("Used", "$20.00"),
+            listing("Brand New", "$50.00"),
+            listing("Levi's", "$5.00"),
+        ];
+
+        let stats = summarize(&listings);
+        assert_eq!(
+            stats,
+            vec![
+                ConditionStats { condition: Some(Condition::New), count: 1, average_price: Some(50.0) },
+                ConditionStats { condition: Some(Condition::Used), count: 2, average_price: Some(15.0) },
+                ConditionStats { condition: None, count: 1, average_price: Some(5.0) },
+            ]
+        );
+    }
+}
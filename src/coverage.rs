@@ -0,0 +1,208 @@
+use crate::app::Listing;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Drop in a field's fill rate, compared to the previous run, that is reported as a likely
+/// selector breakage.
+pub const DROP_THRESHOLD: f64 = 0.25;
+
+/// Returns whether a listing has a value for one field.
+type HasField = fn(&Listing) -> bool;
+
+/// Fields covered by the report: name, the card selector field that fills it (if any) and
+/// whether a listing has it.
+const FIELDS: [(&str, Option<&str>, HasField); 11] = [
+    ("title", Some("title"), |listing| !listing.title.is_empty()),
+    ("price", Some("price"), |listing| !listing.price.is_empty()),
+    ("shipping", Some("shipping"), |listing| listing.shipping.is_some()),
+    ("condition", Some("condition"), |listing| listing.condition.is_some()),
+    ("location", Some("location"), |listing| listing.location.is_some()),
+    ("seller", Some("seller"), |listing| listing.seller.is_some()),
+    ("seller_feedback", Some("seller"), |listing| listing.seller_feedback.is_some()),
+    ("item_id", Some("link"), |listing| listing.item_id.is_some()),
+    ("url", Some("link"), |listing| listing.url.is_some()),
+    ("item_specifics", None, |listing| !listing.item_specifics.is_empty()),
+    ("description", None, |listing| listing.description.is_some()),
+];
+
+/// Counts how often each fallback selector supplied a card field while parsing a page.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SelectorHits {
+    /// The card selector that found the listing cards.
+    pub card_selector: Option<String>,
+    fields: BTreeMap<String, BTreeMap<String, usize>>,
+}
+
+impl SelectorHits {
+    /// Records that `selector` supplied a value for `field`.
+    pub fn record(&mut self, field: &str, selector: &str) {
+        *self
+            .fields
+            .entry(field.to_string())
+            .or_default()
+            .entry(selector.to_string())
+            .or_default() += 1;
+    }
+
+    /// Returns the selector that supplied `field` most often, if any did.
+    pub fn winner(&self, field: &str) -> Option<&str> {
+        self.fields
+            .get(field)?
+            .iter()
+            .max_by_key(|(_, count)| **count)
+            .map(|(selector, _)| selector.as_str())
+    }
+}
+
+/// Fill rate of one field over the listings of a run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldCoverage {
+    /// Name of the field.
+    pub field: String,
+    /// Number of listings that have the field.
+    pub filled: usize,
+    /// Number of listings.
+    pub total: usize,
+    /// The fallback selector that supplied the field most often.
+    pub selector: Option<String>,
+}
+
+impl FieldCoverage {
+    /// Returns the share of listings that have the field, from 0.0 to 1.0.
+    pub fn rate(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.filled as f64 / self.total as f64
+        }
+    }
+}
+
+/// A field whose fill rate dropped sharply since the previous run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverageDrop {
+    /// Name of the field.
+    pub field: String,
+    /// Fill rate of the previous run.
+    pub previous: f64,
+    /// Fill rate of this run.
+    pub current: f64,
+}
+
+impl fmt::Display for CoverageDrop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} dropped from {:.0}% to {:.0}%",
+            self.field,
+            self.previous * 100.0,
+            self.current * 100.0
+        )
+    }
+}
+
+/// Per-field fill rates of a scrape, kept per seller to spot selectors that stopped matching.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CoverageReport {
+    /// The card selector that found the listing cards.
+    pub card_selector: Option<String>,
+    /// Coverage of every field, in card order.
+    pub fields: Vec<FieldCoverage>,
+}
+
+impl CoverageReport {
+    /// Computes the coverage of `listings`, crediting each field to its winning selector in
+    /// `hits`.
+    pub fn compute(listings: &[Listing], hits: &SelectorHits) -> Self {
+        let fields = FIELDS
+            .iter()
+            .map(|(field, selector_field, has)| FieldCoverage {
+                field: field.to_string(),
+                filled: listings.iter().filter(|listing| has(listing)).count(),
+                total: listings.len(),
+                selector: selector_field.and_then(|name| hits.winner(name)).map(str::to_string),
+            })
+            .collect();
+        Self {
+            card_selector: hits.card_selector.clone(),
+            fields,
+        }
+    }
+
+    /// Returns the fields whose fill rate fell by at least [`DROP_THRESHOLD`] since `previous`.
+    pub fn drops(&self, previous: &CoverageReport) -> Vec<CoverageDrop> {
+        self.fields
+            .iter()
+            .filter(|coverage| coverage.total > 0)
+            .filter_map(|coverage| {
+                let before = previous
+                    .fields
+                    .iter()
+                    .find(|before| before.field == coverage.field && before.total > 0)?;
+                (before.rate() - coverage.rate() >= DROP_THRESHOLD).then(|| CoverageDrop {
+                    field: coverage.field.clone(),
+                    previous: before.rate(),
+                    current: coverage.rate(),
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the file the last coverage report of `seller` is stored in.
+    pub fn path_for(seller: &str) -> PathBuf {
+        PathBuf::from(format!(".ebay_coverage_{}.json", seller))
+    }
+
+    /// Loads the report at `path`, returning `None` when there is none.
+    pub fn load(path: &Path) -> color_eyre::Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let report: Self = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        info!("Loaded previous coverage report from {}", path.display());
+        Ok(Some(report))
+    }
+
+    /// Writes the report to `path` via a temporary file.
+    pub fn save(&self, path: &Path) -> color_eyre::Result<()> {
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_and_drops() {
+        let listing = |shipping: Option<&str>| Listing {
+            title: "Levi's 505".to_string(),
+            price: "$25.00".to_string(),
+            shipping: shipping.map(str::to_string),
+            ..Listing::default()
+        };
+        let mut hits = SelectorHits::default();
+        hits.record("shipping", ".s-card__attribute-row");
+        hits.record("shipping", ".s-card__attribute-row");
+        hits.record("shipping", ".su-styled-text");
+
+        let previous = CoverageReport::compute(&[listing(Some("Free delivery")), listing(Some("+$5.00 shipping"))], &hits);
+        let shipping = previous.fields.iter().find(|coverage| coverage.field == "shipping").unwrap();
+        assert_eq!((shipping.filled, shipping.total), (2, 2));
+        assert_eq!(shipping.selector.as_deref(), Some(".s-card__attribute-row"));
+
+        let current = CoverageReport::compute(&[listing(None), listing(Some("Free delivery"))], &SelectorHits::default());
+        let drops = current.drops(&previous);
+        assert_eq!(drops.len(), 1);
+        assert_eq!(drops[0].to_string(), "shipping dropped from 100% to 50%");
+        assert!(previous.drops(&previous).is_empty());
+        // An empty run says nothing about the selectors
+        assert!(CoverageReport::compute(&[], &hits).drops(&previous).is_empty());
+    }
+}
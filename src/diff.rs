@@ -0,0 +1,292 @@
+use crate::app::Listing;
+use crate::export::write_atomically;
+use crate::import;
+use crate::store::Store;
+use color_eyre::eyre::eyre;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// One side of a comparison: an exported file or a run stored in the database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffSource {
+    /// A run recorded in the SQLite store, written as `run:<ID>`.
+    Run(i64),
+    /// A previously exported file.
+    File(PathBuf),
+}
+
+impl FromStr for DiffSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix("run:") {
+            Some(run_id) => run_id
+                .parse()
+                .map(Self::Run)
+                .map_err(|_| format!("invalid run ID '{}'", run_id)),
+            None => Ok(Self::File(PathBuf::from(s))),
+        }
+    }
+}
+
+impl DiffSource {
+    /// Loads the listings of this source and returns them with a human readable label.
+    pub fn load(&self, store: Option<&Store>) -> color_eyre::Result<(String, Vec<Listing>)> {
+        match self {
+            Self::Run(run_id) => {
+                let store = store.ok_or_else(|| eyre!("a database is required to load run {}", run_id))?;
+                Ok((format!("run {}", run_id), store.run_listings(*run_id)?))
+            }
+            Self::File(path) => Ok((path.display().to_string(), import::load_listings(path)?)),
+        }
+    }
+}
+
+/// What changed about a listing present in both results.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// The price was changed.
+    Price,
+    /// The title was edited.
+    Title,
+    /// The available quantity changed.
+    Quantity,
+}
+
+impl ChangeKind {
+    /// Short label used in reports.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Price => "price",
+            Self::Title => "title",
+            Self::Quantity => "quantity",
+        }
+    }
+}
+
+/// A single field change on a listing that exists in both results.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListingChange {
+    /// Item ID of the changed listing.
+    pub item_id: String,
+    /// Current title of the listing.
+    pub title: String,
+    /// Which field changed.
+    pub kind: ChangeKind,
+    /// Value in the older result.
+    pub old_value: String,
+    /// Value in the newer result.
+    pub new_value: String,
+}
+
+/// Differences between two scrape results, matched by `item_id`.
+#[derive(Debug, Clone, Default)]
+pub struct ListingDiff {
+    /// Description of the older result, e.g. a file name or run ID.
+    pub old_label: String,
+    /// Description of the newer result.
+    pub new_label: String,
+    /// Listings only present in the newer result.
+    pub added: Vec<Listing>,
+    /// Listings only present in the older result, i.e. ended or removed.
+    pub removed: Vec<Listing>,
+    /// Field changes on listings present in both results.
+    pub changes: Vec<ListingChange>,
+}
+
+impl ListingDiff {
+    /// Loads and compares two sources. A missing source defaults to the latest runs of `seller`
+    /// in the database: the newer side to the latest run and the older side to the run before it.
+    pub fn load(
+        old: Option<&DiffSource>,
+        new: Option<&DiffSource>,
+        database: &Path,
+        seller: &str,
+    ) -> color_eyre::Result<Self> {
+        let store = if database.exists() {
+            Some(Store::open(database)?)
+        } else {
+            None
+        };
+        let recent_runs = match &store {
+            Some(store) => store.recent_runs(seller, 2)?,
+            None => Vec::new(),
+        };
+
+        let new = match new {
+            Some(source) => source.clone(),
+            None => DiffSource::Run(
+                *recent_runs
+                    .first()
+                    .ok_or_else(|| eyre!("no recorded runs of {} to compare", seller))?,
+            ),
+        };
+        let old = match old {
+            Some(source) => source.clone(),
+            None => DiffSource::Run(
+                *recent_runs
+                    .get(1)
+                    .ok_or_else(|| eyre!("need at least two recorded runs of {} to compare", seller))?,
+            ),
+        };
+
+        let (old_label, old_listings) = old.load(store.as_ref())?;
+        let (new_label, new_listings) = new.load(store.as_ref())?;
+        Ok(Self::compare(&old_label, &old_listings, &new_label, &new_listings))
+    }
+
+    /// Compares two scrape results. Listings without an item ID cannot be matched and are ignored.
+    pub fn compare(old_label: &str, old: &[Listing], new_label: &str, new: &[Listing]) -> Self {
+        let old_by_id: HashMap<&str, &Listing> = old
+            .iter()
+            .filter_map(|listing| Some((listing.item_id.as_deref()?, listing)))
+            .collect();
+        let new_by_id: HashMap<&str, &Listing> = new
+            .iter()
+            .filter_map(|listing| Some((listing.item_id.as_deref()?, listing)))
+            .collect();
+
+        let mut diff = Self {
+            old_label: old_label.to_string(),
+            new_label: new_label.to_string(),
+            ..Self::default()
+        };
+
+        for listing in new {
+            let Some(item_id) = listing.item_id.as_deref() else {
+                continue;
+            };
+            let Some(previous) = old_by_id.get(item_id) else {
+                diff.added.push(listing.clone());
+                continue;
+            };
+
+            let mut record = |kind: ChangeKind, old_value: String, new_value: String| {
+                if old_value != new_value {
+                    diff.changes.push(ListingChange {
+                        item_id: item_id.to_string(),
+                        title: listing.title.clone(),
+                        kind,
+                        old_value,
+                        new_value,
+                    });
+                }
+            };
+            record(ChangeKind::Price, previous.price.trim().to_string(), listing.price.trim().to_string());
+            record(ChangeKind::Title, previous.title.trim().to_string(), listing.title.trim().to_string());
+            let quantity = |listing: &Listing| listing.quantity_available.map_or(String::new(), |q| q.to_string());
+            record(ChangeKind::Quantity, quantity(previous), quantity(listing));
+        }
+
+        diff.removed = old
+            .iter()
+            .filter(|listing| {
+                listing
+                    .item_id
+                    .as_deref()
+                    .is_some_and(|item_id| !new_by_id.contains_key(item_id))
+            })
+            .cloned()
+            .collect();
+
+        diff
+    }
+
+    /// Returns true when nothing changed between the two results.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changes.is_empty()
+    }
+
+    /// Returns the changes of the given kind.
+    pub fn changes_of(&self, kind: ChangeKind) -> impl Iterator<Item = &ListingChange> {
+        self.changes.iter().filter(move |change| change.kind == kind)
+    }
+
+    /// Writes the diff as a CSV report with one row per change.
+    pub fn write_report(&self, path: &Path) -> color_eyre::Result<()> {
+        write_atomically(path, |writer| self.write_report_to(writer))
+    }
+
+    fn write_report_to(&self, writer: &mut dyn Write) -> color_eyre::Result<()> {
+        let mut wtr = csv::Writer::from_writer(writer);
+        wtr.write_record(["change", "item_id", "title", "old_value", "new_value", "url"])?;
+
+        for listing in &self.added {
+            wtr.write_record([
+                "new",
+                listing.item_id.as_deref().unwrap_or(""),
+                &listing.title,
+                "",
+                &listing.price,
+                listing.url.as_deref().unwrap_or(""),
+            ])?;
+        }
+        for listing in &self.removed {
+            wtr.write_record([
+                "ended",
+                listing.item_id.as_deref().unwrap_or(""),
+                &listing.title,
+                &listing.price,
+                "",
+                listing.url.as_deref().unwrap_or(""),
+            ])?;
+        }
+        for change in &self.changes {
+            wtr.write_record([
+                change.kind.label(),
+                &change.item_id,
+                &change.title,
+                &change.old_value,
+                &change.new_value,
+                "",
+            ])?;
+        }
+
+        wtr.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listing(item_id: &str, title: &str, price: &str) -> Listing {
+        Listing {
+            title: title.to_string(),
+            price: price.to_string(),
+            item_id: Some(item_id.to_string()),
+            ..Listing::default()
+        }
+    }
+
+    #[test]
+    fn test_compare_runs() {
+        let old = vec![
+            listing("1", "Levi's 505 Jeans", "$15.00"),
+            listing("2", "Pure Heroine CD", "$7.00"),
+            listing("3", "GameCube Haunted Mansion", "$25.00"),
+        ];
+        let mut new = vec![
+            listing("1", "Levi's 505 Jeans", "$12.00"),
+            listing("3", "GameCube Haunted Mansion CIB", "$25.00"),
+            listing("4", "Tori Amos CD", "$4.25"),
+        ];
+        new[1].quantity_available = Some(2);
+
+        let diff = ListingDiff::compare("yesterday", &old, "today", &new);
+
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].item_id.as_deref(), Some("4"));
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].item_id.as_deref(), Some("2"));
+
+        let price: Vec<_> = diff.changes_of(ChangeKind::Price).collect();
+        assert_eq!(price.len(), 1);
+        assert_eq!((price[0].old_value.as_str(), price[0].new_value.as_str()), ("$15.00", "$12.00"));
+        assert_eq!(diff.changes_of(ChangeKind::Title).count(), 1);
+        assert_eq!(diff.changes_of(ChangeKind::Quantity).count(), 1);
+    }
+}
@@ -32,14 +32,18 @@ pub enum Event {
 pub enum AppEvent {
     /// Quit the application.
     Quit,
-    /// Connect to the webdriver client.
-    Connect,
-    /// The client is ready.
-    ClientReady,
-    /// Set the loading progress.
-    SetProgress(f64, String),
-    /// Initialize the application.
-    Init(String),
+    /// Start a stage of the scrape pipeline.
+    RunStage(crate::pipeline::Stage),
+    /// A stage has done some of its work (stage, units done, units in total).
+    StageProgress(crate::pipeline::Stage, usize, usize),
+    /// A stage finished with a summary of its result.
+    StageFinished(crate::pipeline::Stage, String),
+    /// A stage failed with an error.
+    StageFailed(crate::pipeline::Stage, String),
+    /// A stage was cancelled by the user, with a summary of the partial result.
+    StageCancelled(crate::pipeline::Stage, String),
+    /// Set the status message.
+    SetStatus(String),
     /// Scrape the feedback score.
     ScrapeFeedback(String),
     /// Scrape the number of items sold.
@@ -62,18 +66,20 @@ pub enum AppEvent {
     NavigationComplete,
     /// Navigation failed.
     NavigationError(String),
-    /// CAPTCHA detected on page.
-    CaptchaDetected,
+    /// CAPTCHA or another bot challenge detected on page.
+    CaptchaDetected(crate::captcha::CaptchaDetected),
     /// CAPTCHA has been resolved by user.
     CaptchaResolved,
-    /// Scraping operations completed.
-    ScrapingComplete,
-    /// Scrape listings from current page.
-    ScrapeListings(Vec<crate::app::Listing>),
-    /// Enrich listings with detailed information.
-    EnrichListings,
+    /// A CAPTCHA was not solved in time and the scrape is being aborted.
+    CaptchaTimedOut,
+    /// Listings scraped from the current page, with the selectors that supplied their fields.
+    ScrapeListings(Vec<crate::app::Listing>, crate::coverage::SelectorHits),
     /// Enriched listings ready for saving.
     EnrichedListings(Vec<crate::app::Listing>),
+    /// Status update from an enrichment worker (worker index, status).
+    WorkerProgress(usize, String),
+    /// A single listing has been enriched (index into the listings, enriched listing).
+    ListingEnriched(usize, Box<crate::app::Listing>),
 }
 
 
@@ -0,0 +1,731 @@
+use crate::app::{Listing, parse_price};
+use crate::marketplace::Marketplace;
+use crate::parquet_export::{self, ParquetExporter};
+use crate::store::RunSummary;
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use color_eyre::eyre::eyre;
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use rust_xlsxwriter::{Format, FormatAlign, Workbook, Worksheet};
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// Version of the export layout. Bump it whenever a column is renamed, removed or changes type;
+/// adding a column at the end does not require a bump.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// A column of the CSV, JSON and XLSX exports. The declaration order is the stable default order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "snake_case")]
+pub enum Column {
+    /// Listing title.
+    Title,
+    /// Price as shown on the card, e.g. `$12.99`.
+    Price,
+    /// Shipping cost text.
+    Shipping,
+    /// Item condition text.
+    Condition,
+    /// Number of watchers.
+    Watchers,
+    /// Seller username.
+    Seller,
+    /// Seller feedback score.
+    SellerFeedback,
+    /// Whether Buy It Now is offered.
+    BuyItNow,
+    /// Whether Best Offer is accepted.
+    AcceptsOffers,
+    /// Item location.
+    Location,
+    /// Available quantity.
+    QuantityAvailable,
+    /// Whether the listing is marked as new.
+    IsNewListing,
+    /// eBay item ID.
+    ItemId,
+    /// Item page URL.
+    Url,
+    /// Extra notes from the card, a list of strings.
+    Notes,
+    /// Item specifics as `Name: value` strings, a list of strings.
+    ItemSpecifics,
+    /// Seller description.
+    Description,
+    /// Number of bids of an auction.
+    Bids,
+    /// Whether the card is a sponsored or promoted placement.
+    Sponsored,
+    /// Selected variation of a multi-variation listing.
+    VariationId,
+    /// Standard eBay condition the condition text maps to, e.g. `Used` for `Pre-Owned`.
+    ConditionCategory,
+    /// How the item is sold, `auction` or `buy_it_now`.
+    Format,
+}
+
+impl Column {
+    /// Every column in the stable default order.
+    pub const ALL: [Column; 22] = [
+        Self::Title,
+        Self::Price,
+        Self::Shipping,
+        Self::Condition,
+        Self::Watchers,
+        Self::Seller,
+        Self::SellerFeedback,
+        Self::BuyItNow,
+        Self::AcceptsOffers,
+        Self::Location,
+        Self::QuantityAvailable,
+        Self::IsNewListing,
+        Self::ItemId,
+        Self::Url,
+        Self::Notes,
+        Self::ItemSpecifics,
+        Self::Description,
+        Self::Bids,
+        Self::Sponsored,
+        Self::VariationId,
+        Self::ConditionCategory,
+        Self::Format,
+    ];
+
+    /// Header used for the column in every format.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Title => "title",
+            Self::Price => "price",
+            Self::Shipping => "shipping",
+            Self::Condition => "condition",
+            Self::Watchers => "watchers",
+            Self::Seller => "seller",
+            Self::SellerFeedback => "seller_feedback",
+            Self::BuyItNow => "buy_it_now",
+            Self::AcceptsOffers => "accepts_offers",
+            Self::Location => "location",
+            Self::QuantityAvailable => "quantity_available",
+            Self::IsNewListing => "is_new_listing",
+            Self::ItemId => "item_id",
+            Self::Url => "url",
+            Self::Notes => "notes",
+            Self::ItemSpecifics => "item_specifics",
+            Self::Description => "description",
+            Self::Bids => "bids",
+            Self::Sponsored => "sponsored",
+            Self::VariationId => "variation_id",
+            Self::ConditionCategory => "condition_category",
+            Self::Format => "format",
+        }
+    }
+
+    /// Typed value of the column for `listing`.
+    pub fn value(&self, listing: &Listing) -> serde_json::Value {
+        match self {
+            Self::Title => listing.title.clone().into(),
+            Self::Price => listing.price.clone().into(),
+            Self::Shipping => listing.shipping.clone().into(),
+            Self::Condition => listing.condition.clone().into(),
+            Self::Watchers => listing.watchers.into(),
+            Self::Seller => listing.seller.clone().into(),
+            Self::SellerFeedback => listing.seller_feedback.clone().into(),
+            Self::BuyItNow => listing.buy_it_now.into(),
+            Self::AcceptsOffers => listing.accepts_offers.into(),
+            Self::Location => listing.location.clone().into(),
+            Self::QuantityAvailable => listing.quantity_available.into(),
+            Self::IsNewListing => listing.is_new_listing.into(),
+            Self::ItemId => listing.item_id.clone().into(),
+            Self::Url => listing.url.clone().into(),
+            Self::Notes => listing.notes.clone().into(),
+            Self::ItemSpecifics => listing.item_specifics.clone().into(),
+            Self::Description => listing.description.clone().into(),
+            Self::Bids => listing.bids.into(),
+            Self::Sponsored => listing.sponsored.into(),
+            Self::VariationId => listing.variation_id.clone().into(),
+            Self::ConditionCategory => listing.condition_category().map(|condition| condition.to_string()).into(),
+            Self::Format => listing.format.map(|format| format.as_str()).into(),
+        }
+    }
+
+    /// Value of the column as a CSV cell. Missing values are empty and lists are JSON arrays.
+    pub fn cell(&self, listing: &Listing) -> String {
+        match self.value(listing) {
+            serde_json::Value::Null => String::new(),
+            serde_json::Value::String(text) => text,
+            value => value.to_string(),
+        }
+    }
+}
+
+/// One listing restricted to the selected columns, serialized as an object with the schema
+/// version first and the columns in the selected order.
+struct Record<'a> {
+    columns: &'a [Column],
+    listing: &'a Listing,
+}
+
+impl Serialize for Record<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.columns.len() + 1))?;
+        map.serialize_entry("schema_version", &SCHEMA_VERSION)?;
+        for column in self.columns {
+            map.serialize_entry(column.name(), &column.value(self.listing))?;
+        }
+        map.end()
+    }
+}
+
+/// Writes scraped listings in one output format.
+pub trait Exporter {
+    /// Serializes `listings` scraped in the run described by `summary` to `writer`.
+    fn export(&self, summary: &RunSummary, listings: &[Listing], writer: &mut dyn Write) -> color_eyre::Result<()>;
+}
+
+/// Comma separated values with one row per listing. The first column is always
+/// `schema_version`. List fields are written as JSON arrays so values containing separators
+/// survive a round trip.
+#[derive(Debug)]
+pub struct CsvExporter {
+    /// Columns to write, in order.
+    pub columns: Vec<Column>,
+}
+
+impl Exporter for CsvExporter {
+    fn export(&self, _summary: &RunSummary, listings: &[Listing], writer: &mut dyn Write) -> color_eyre::Result<()> {
+        let mut wtr = csv::Writer::from_writer(writer);
+        wtr.write_record(std::iter::once("schema_version").chain(self.columns.iter().map(Column::name)))?;
+
+        let version = SCHEMA_VERSION.to_string();
+        for listing in listings {
+            let cells = self.columns.iter().map(|column| column.cell(listing));
+            wtr.write_record(std::iter::once(version.clone()).chain(cells))?;
+        }
+
+        wtr.flush()?;
+        Ok(())
+    }
+}
+
+/// A single pretty-printed JSON array of listing objects.
+#[derive(Debug)]
+pub struct JsonExporter {
+    /// Columns to write, in order.
+    pub columns: Vec<Column>,
+}
+
+impl Exporter for JsonExporter {
+    fn export(&self, _summary: &RunSummary, listings: &[Listing], writer: &mut dyn Write) -> color_eyre::Result<()> {
+        let records: Vec<_> = listings
+            .iter()
+            .map(|listing| Record { columns: &self.columns, listing })
+            .collect();
+        serde_json::to_writer_pretty(&mut *writer, &records)?;
+        writeln!(writer)?;
+        Ok(())
+    }
+}
+
+/// JSON Lines, one listing object per line.
+#[derive(Debug)]
+pub struct JsonLinesExporter {
+    /// Columns to write, in order.
+    pub columns: Vec<Column>,
+}
+
+impl Exporter for JsonLinesExporter {
+    fn export(&self, _summary: &RunSummary, listings: &[Listing], writer: &mut dyn Write) -> color_eyre::Result<()> {
+        for listing in listings {
+            serde_json::to_writer(&mut *writer, &Record { columns: &self.columns, listing })?;
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Excel workbook with a typed Listings sheet and a Seller Summary sheet.
+#[derive(Debug)]
+pub struct XlsxExporter {
+    /// Columns of the Listings sheet, in order.
+    pub columns: Vec<Column>,
+    /// Marketplace whose currency the price cells are formatted in.
+    pub marketplace: Marketplace,
+}
+
+impl XlsxExporter {
+    /// Returns the number format of price cells, e.g. `#,##0.00 "GBP"`.
+    fn money_format(&self) -> Format {
+        Format::new().set_num_format(format!("#,##0.00 \"{}\"", self.marketplace.currency()))
+    }
+
+    /// Header of `column` in the Listings sheet.
+    fn title(column: Column) -> &'static str {
+        match column {
+            Column::Title => "Title",
+            Column::Price => "Price",
+            Column::Shipping => "Shipping",
+            Column::Condition => "Condition",
+            Column::Watchers => "Watchers",
+            Column::Seller => "Seller",
+            Column::SellerFeedback => "Seller Feedback",
+            Column::BuyItNow => "Buy It Now",
+            Column::AcceptsOffers => "Accepts Offers",
+            Column::Location => "Location",
+            Column::QuantityAvailable => "Quantity",
+            Column::IsNewListing => "New Listing",
+            Column::ItemId => "Item ID",
+            Column::Url => "URL",
+            Column::Notes => "Notes",
+            Column::ItemSpecifics => "Item Specifics",
+            Column::Description => "Description",
+            Column::Bids => "Bids",
+            Column::Sponsored => "Sponsored",
+            Column::VariationId => "Variation ID",
+            Column::ConditionCategory => "Condition Category",
+            Column::Format => "Format",
+        }
+    }
+
+    fn write_listings(&self, sheet: &mut Worksheet, listings: &[Listing]) -> color_eyre::Result<()> {
+        let header = Format::new().set_bold();
+        let money = self.money_format();
+        let wrapped = Format::new().set_text_wrap().set_align(FormatAlign::Top);
+
+        // Every item specific name gets its own column
+        let specific_names: BTreeSet<&str> = listings
+            .iter()
+            .flat_map(|listing| listing.item_specifics.iter())
+            .filter_map(|specific| specific.split_once(':'))
+            .map(|(name, _)| name.trim())
+            .collect();
+
+        // The price is followed by the price as shown, and item specifics take one column each
+        let mut headers = Vec::new();
+        for column in &self.columns {
+            match column {
+                Column::Price => headers.extend(["Price", "Price Text"]),
+                Column::ItemSpecifics => headers.extend(specific_names.iter()),
+                column => headers.push(Self::title(*column)),
+            }
+        }
+
+        sheet.set_name("Listings")?;
+        for (column, title) in headers.iter().enumerate() {
+            sheet.write_string_with_format(0, column as u16, *title, &header)?;
+        }
+        sheet.set_freeze_panes(1, 0)?;
+
+        for (index, listing) in listings.iter().enumerate() {
+            let row = index as u32 + 1;
+            let mut cell = 0u16;
+            for column in &self.columns {
+                match column {
+                    Column::Price => {
+                        if let Some(price) = parse_price(&listing.price) {
+                            sheet.write_number_with_format(row, cell, price, &money)?;
+                        }
+                        cell += 1;
+                        sheet.write_string(row, cell, &listing.price)?;
+                    }
+                    Column::Shipping => {
+                        if let Some(shipping) = &listing.shipping {
+                            match parse_price(shipping) {
+                                Some(cost) => sheet.write_number_with_format(row, cell, cost, &money)?,
+                                None => sheet.write_string(row, cell, shipping)?,
+                            };
+                        }
+                    }
+                    Column::Url => {
+                        if let Some(url) = &listing.url {
+                            sheet.write_url(row, cell, url.as_str())?;
+                        }
+                    }
+                    Column::Notes => {
+                        sheet.write_string_with_format(row, cell, listing.notes.join("\n"), &wrapped)?;
+                    }
+                    Column::Description => {
+                        if let Some(description) = &listing.description {
+                            sheet.write_string_with_format(row, cell, description, &wrapped)?;
+                        }
+                    }
+                    Column::ItemSpecifics => {
+                        for specific in &listing.item_specifics {
+                            let Some((name, value)) = specific.split_once(':') else {
+                                continue;
+                            };
+                            if let Some(offset) = specific_names.iter().position(|known| *known == name.trim()) {
+                                sheet.write_string(row, cell + offset as u16, value.trim())?;
+                            }
+                        }
+                        cell += specific_names.len() as u16;
+                        continue;
+                    }
+                    column => match column.value(listing) {
+                        serde_json::Value::Null => {}
+                        serde_json::Value::Bool(value) => {
+                            sheet.write_boolean(row, cell, value)?;
+                        }
+                        serde_json::Value::Number(value) => {
+                            sheet.write_number(row, cell, value.as_f64().unwrap_or_default())?;
+                        }
+                        _ => {
+                            sheet.write_string(row, cell, column.cell(listing))?;
+                        }
+                    },
+                }
+                cell += 1;
+            }
+        }
+
+        sheet.autofit();
+        let mut cell = 0u16;
+        for column in &self.columns {
+            match column {
+                Column::Title | Column::Description => {
+                    sheet.set_column_width(cell, 60)?;
+                }
+                Column::Notes => {
+                    sheet.set_column_width(cell, 40)?;
+                }
+                _ => {}
+            }
+            cell += match column {
+                Column::Price => 2,
+                Column::ItemSpecifics => specific_names.len() as u16,
+                _ => 1,
+            };
+        }
+        Ok(())
+    }
+
+    fn write_summary(&self, sheet: &mut Worksheet, summary: &RunSummary, listings: &[Listing]) -> color_eyre::Result<()> {
+        let label = Format::new().set_bold();
+        let money = self.money_format();
+
+        sheet.set_name("Seller Summary")?;
+        let rows: [(&str, Option<f64>, Option<String>); 8] = [
+            ("Seller", None, Some(summary.seller.clone())),
+            ("Feedback Score", None, summary.feedback_score.clone()),
+            ("Items Sold", summary.items_sold.map(f64::from), None),
+            ("Followers", summary.follower_count.map(f64::from), None),
+            ("Active Listings", Some(listings.len() as f64), None),
+            ("Run Started", None, Some(summary.started_at.to_rfc3339())),
+            ("Run Finished", None, Some(summary.finished_at.to_rfc3339())),
+            ("Schema Version", Some(f64::from(SCHEMA_VERSION)), None),
+        ];
+        let total_row = rows.len() as u32;
+        for (row, (name, number, text)) in rows.into_iter().enumerate() {
+            let row = row as u32;
+            sheet.write_string_with_format(row, 0, name, &label)?;
+            match (number, text) {
+                (Some(number), _) => sheet.write_number(row, 1, number)?,
+                (None, Some(text)) => sheet.write_string(row, 1, text)?,
+                (None, None) => sheet.write_string(row, 1, "N/A")?,
+            };
+        }
+
+        let total: f64 = listings.iter().filter_map(Listing::price_value).sum();
+        sheet.write_string_with_format(total_row, 0, "Total Listed Value", &label)?;
+        sheet.write_number_with_format(total_row, 1, total, &money)?;
+        sheet.autofit();
+        Ok(())
+    }
+}
+
+impl Exporter for XlsxExporter {
+    fn export(&self, summary: &RunSummary, listings: &[Listing], writer: &mut dyn Write) -> color_eyre::Result<()> {
+        let mut workbook = Workbook::new();
+        self.write_listings(workbook.add_worksheet(), listings)?;
+        self.write_summary(workbook.add_worksheet(), summary, listings)?;
+        writer.write_all(&workbook.save_to_buffer()?)?;
+        Ok(())
+    }
+}
+
+/// Output formats listings can be exported in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ExportFormat {
+    /// Comma separated values.
+    #[default]
+    Csv,
+    /// A JSON array.
+    Json,
+    /// JSON Lines, one object per line.
+    Jsonl,
+    /// An Excel workbook.
+    Xlsx,
+    /// Apache Parquet, with item specifics in a second file next to it.
+    Parquet,
+}
+
+impl ExportFormat {
+    /// File extension used for this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+            Self::Jsonl => "jsonl",
+            Self::Xlsx => "xlsx",
+            Self::Parquet => "parquet",
+        }
+    }
+
+    /// Guesses the format from the extension of `path`.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            "jsonl" | "ndjson" => Some(Self::Jsonl),
+            "xlsx" => Some(Self::Xlsx),
+            "parquet" => Some(Self::Parquet),
+            _ => None,
+        }
+    }
+
+    /// Returns the exporter writing this format. `columns` select and order the columns of CSV,
+    /// JSON and XLSX exports, with XLSX prices formatted in the currency of `marketplace`; Parquet
+    /// always uses its fixed typed layout.
+    pub fn exporter(&self, columns: &[Column], marketplace: Marketplace) -> Box<dyn Exporter> {
+        let columns = columns.to_vec();
+        match self {
+            Self::Csv => Box::new(CsvExporter { columns }),
+            Self::Json => Box::new(JsonExporter { columns }),
+            Self::Jsonl => Box::new(JsonLinesExporter { columns }),
+            Self::Xlsx => Box::new(XlsxExporter { columns, marketplace }),
+            Self::Parquet => Box::new(ParquetExporter),
+        }
+    }
+}
+
+/// Writes a file through `write`, first to a hidden temporary file next to `path` which is then
+/// renamed into place, so readers never see a partially written file.
+pub fn write_atomically(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> color_eyre::Result<()>,
+) -> color_eyre::Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| eyre!("{} is not a file path", path.display()))?;
+    let tmp_path = path.with_file_name(format!(".{}.tmp", file_name.to_string_lossy()));
+
+    let result: color_eyre::Result<()> = File::create(&tmp_path).map_err(Into::into).and_then(|file| {
+        let mut writer = BufWriter::new(file);
+        write(&mut writer)?;
+        writer.into_inner()?.sync_all()?;
+        Ok(())
+    });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp_path);
+    }
+    result?;
+    std::fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+/// Variables of file name templates, see [`render_file_name`].
+pub const FILE_NAME_VARIABLES: [&str; 4] = ["seller", "date", "time", "format"];
+
+/// Checks that `template` only uses [`FILE_NAME_VARIABLES`], so a typo or an unsupported
+/// variable such as `{query}` is reported instead of ending up in the file name.
+pub fn parse_file_name_template(template: &str) -> Result<String, String> {
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("unclosed `{{` in {}", template))?;
+        let name = &rest[start + 1..start + end];
+        if !FILE_NAME_VARIABLES.contains(&name) {
+            return Err(format!(
+                "unknown variable {{{}}}, expected one of {}",
+                name,
+                FILE_NAME_VARIABLES.map(|variable| format!("{{{}}}", variable)).join(", ")
+            ));
+        }
+        rest = &rest[start + end + 1..];
+    }
+    Ok(template.to_string())
+}
+
+/// Expands a file name template. Supported variables are `{seller}`, `{date}` (`YYYYMMDD`),
+/// `{time}` (`HHMMSS`) and `{format}` (the format's file extension). The whole store is always
+/// scraped, so there is no search query to name the file after.
+pub fn render_file_name(
+    template: &str,
+    seller: &str,
+    date: DateTime<Utc>,
+    format: ExportFormat,
+) -> String {
+    // Variables come from user input, so keep them from introducing path separators
+    let sanitize = |value: &str| -> String {
+        value
+            .chars()
+            .map(|c| if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+            .collect()
+    };
+    template
+        .replace("{seller}", &sanitize(seller))
+        .replace("{date}", &date.format("%Y%m%d").to_string())
+        .replace("{time}", &date.format("%H%M%S").to_string())
+        .replace("{format}", format.extension())
+}
+
+/// Writes the selected `columns` of `listings` of the run described by `summary` on `marketplace`
+/// to `path`. An explicit `format` wins, otherwise it is taken from the file extension. Parquet exports also write the item specifics
+/// table to `<name>_specifics.parquet`.
+pub fn export_listings(
+    path: &Path,
+    format: Option<ExportFormat>,
+    columns: &[Column],
+    marketplace: Marketplace,
+    summary: &RunSummary,
+    listings: &[Listing],
+) -> color_eyre::Result<()> {
+    let format = format
+        .or_else(|| ExportFormat::from_path(path))
+        .ok_or_else(|| eyre!("cannot tell the export format of {}, pass --format", path.display()))?;
+
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    write_atomically(path, |writer| format.exporter(columns, marketplace).export(summary, listings, writer))?;
+
+    if format == ExportFormat::Parquet {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let specifics_path = path.with_file_name(format!("{}_specifics.parquet", stem));
+        parquet_export::write_specifics(&specifics_path, summary, listings)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attributes::ListingFormat;
+
+    fn summary() -> RunSummary {
+        RunSummary {
+            seller: "seller123".to_string(),
+            started_at: Utc::now(),
+            finished_at: Utc::now(),
+            feedback_score: Some("99.5%".to_string()),
+            items_sold: Some(45),
+            follower_count: None,
+            partial: false,
+        }
+    }
+
+    fn sample() -> Listing {
+        Listing {
+            title: "Sample Item".to_string(),
+            price: "$10.00".to_string(),
+            item_id: Some("1234567890".to_string()),
+            notes: vec!["Free returns; 30 days".to_string()],
+            item_specifics: vec!["Brand: Acme".to_string(), "Color: Red".to_string()],
+            ..Listing::default()
+        }
+    }
+
+    #[test]
+    fn test_nested_fields_are_preserved() {
+        let listing = sample();
+
+        let mut json = Vec::new();
+        JsonLinesExporter { columns: Column::ALL.to_vec() }
+            .export(&summary(), std::slice::from_ref(&listing), &mut json).unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(value["notes"][0], "Free returns; 30 days");
+        assert_eq!(value["item_specifics"][1], "Color: Red");
+        assert_eq!(value["schema_version"], SCHEMA_VERSION);
+
+        let mut csv = Vec::new();
+        CsvExporter { columns: Column::ALL.to_vec() }
+            .export(&summary(), std::slice::from_ref(&listing), &mut csv)
+            .unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert!(csv.contains(r#""[""Free returns; 30 days""]""#));
+
+        let mut selected = Vec::new();
+        CsvExporter { columns: vec![Column::Url, Column::Title] }
+            .export(&summary(), &[listing], &mut selected)
+            .unwrap();
+        assert_eq!(String::from_utf8(selected).unwrap(), "schema_version,url,title\n2,,Sample Item\n");
+    }
+
+    #[test]
+    fn test_xlsx_workbook_is_written() {
+        let mut xlsx = Vec::new();
+        let listing = Listing {
+            url: Some("https://www.ebay.co.uk/itm/1234567890".to_string()),
+            condition: Some("Pre-Owned".to_string()),
+            bids: Some(2),
+            sponsored: true,
+            variation_id: Some("987654".to_string()),
+            format: Some(ListingFormat::Auction),
+            ..sample()
+        };
+        XlsxExporter { columns: Column::ALL.to_vec(), marketplace: Marketplace::Uk }
+            .export(&summary(), &[listing], &mut xlsx)
+            .unwrap();
+
+        // Read the parts of the workbook back from the archive
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(xlsx)).unwrap();
+        let mut part = |name: &str| {
+            let mut xml = String::new();
+            std::io::Read::read_to_string(&mut archive.by_name(name).unwrap(), &mut xml).unwrap();
+            xml
+        };
+        // Returns the XML of one cell, e.g. `<c r="B2" s="3"><v>10</v></c>`
+        let cell = |sheet: &str, reference: &str| -> String {
+            let start = sheet.find(&format!("<c r=\"{}\"", reference)).unwrap();
+            let end = start + sheet[start..].find("</c>").unwrap();
+            sheet[start..end].to_string()
+        };
+
+        let workbook = part("xl/workbook.xml");
+        assert!(workbook.contains(r#"<sheet name="Listings""#));
+        assert!(workbook.contains(r#"<sheet name="Seller Summary""#));
+
+        let listings = part("xl/worksheets/sheet1.xml");
+        assert!(listings.contains(r#"<pane ySplit="1" topLeftCell="A2" activePane="bottomLeft" state="frozen"/>"#));
+        // The price is a number cell, next to the price text as a string
+        let price = cell(&listings, "B2");
+        assert!(price.ends_with("<v>10</v>") && !price.contains(r#"t="s""#), "{}", price);
+        assert!(cell(&listings, "C2").contains(r#"t="s""#));
+        assert!(listings.contains(r#"<hyperlink ref="O2""#));
+        assert!(part("xl/worksheets/_rels/sheet1.xml.rels").contains(r#"Target="https://www.ebay.co.uk/itm/1234567890""#));
+        assert!(part("xl/styles.xml").contains("#,##0.00 &quot;GBP&quot;"));
+        // Columns added after the first release are there too, typed where they can be
+        let strings = part("xl/sharedStrings.xml");
+        for text in ["Bids", "Sponsored", "Variation ID", "Condition Category", "Format", "987654", "Used", "auction"] {
+            assert!(strings.contains(&format!("<t>{}</t>", text)), "{}", text);
+        }
+        assert!(cell(&listings, "T2").ends_with("<v>2</v>"));
+        assert!(cell(&listings, "U2").contains(r#"t="b""#));
+
+        // Total Listed Value is the last row of the Seller Summary sheet
+        let summary = part("xl/worksheets/sheet2.xml");
+        assert!(cell(&summary, "B3").ends_with("<v>45</v>"));
+        assert!(cell(&summary, "B9").ends_with("<v>10</v>"));
+    }
+
+    #[test]
+    fn test_render_file_name() {
+        let date = DateTime::parse_from_rfc3339("2025-03-04T05:06:07Z").unwrap().with_timezone(&Utc);
+        let name = render_file_name("{seller}_{date}_{time}.{format}", "a/b", date, ExportFormat::Jsonl);
+        assert_eq!(name, "a_b_20250304_050607.jsonl");
+        assert!(parse_file_name_template("ebay_{seller}_{date}.{format}").is_ok());
+        assert_eq!(
+            parse_file_name_template("ebay_{seller}_{query}.{format}").unwrap_err(),
+            "unknown variable {query}, expected one of {seller}, {date}, {time}, {format}"
+        );
+    }
+
+    #[test]
+    fn test_format_from_extension() {
+        assert_eq!(ExportFormat::from_path(Path::new("out.JSON")), Some(ExportFormat::Json));
+        assert_eq!(ExportFormat::from_path(Path::new("out.ndjson")), Some(ExportFormat::Jsonl));
+        assert_eq!(ExportFormat::from_path(Path::new("out")), None);
+    }
+}
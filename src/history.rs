@@ -0,0 +1,136 @@
+use crate::app::parse_price;
+use crate::export::write_atomically;
+use crate::store::Store;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+
+/// State of a listing at the end of one run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PricePoint {
+    /// Run the snapshot was taken in.
+    pub run_id: i64,
+    /// When the run finished.
+    pub recorded_at: DateTime<Utc>,
+    /// Title at the time.
+    pub title: String,
+    /// Price text as shown on the card.
+    pub price: String,
+    /// Number of watchers at the time.
+    pub watchers: Option<u32>,
+    /// Available quantity at the time.
+    pub quantity: Option<u32>,
+}
+
+/// Time series of price, watchers and quantity for every item of a seller, oldest first.
+#[derive(Debug, Clone, Default)]
+pub struct PriceHistory {
+    series: HashMap<String, Vec<PricePoint>>,
+}
+
+impl PriceHistory {
+    /// Loads the history of every item `seller` has listed across all recorded runs.
+    pub fn load(store: &Store, seller: &str) -> color_eyre::Result<Self> {
+        let mut series: HashMap<String, Vec<PricePoint>> = HashMap::new();
+        for (item_id, point) in store.item_snapshots(seller)? {
+            series.entry(item_id).or_default().push(point);
+        }
+        Ok(Self { series })
+    }
+
+    /// Returns the IDs of every item with recorded history, sorted.
+    pub fn item_ids(&self) -> Vec<&str> {
+        let mut item_ids: Vec<_> = self.series.keys().map(String::as_str).collect();
+        item_ids.sort();
+        item_ids
+    }
+
+    /// Returns the recorded points of `item_id`, oldest first.
+    pub fn points(&self, item_id: &str) -> &[PricePoint] {
+        self.series.get(item_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the prices of `item_id` in cents, skipping runs whose price could not be parsed.
+    pub fn price_cents(&self, item_id: &str) -> Vec<u64> {
+        self.points(item_id)
+            .iter()
+            .filter_map(|point| parse_price(&point.price))
+            .map(|price| (price * 100.0).round() as u64)
+            .collect()
+    }
+
+    /// Returns the watcher counts of `item_id`, skipping runs that did not record one.
+    pub fn watcher_counts(&self, item_id: &str) -> Vec<u64> {
+        self.points(item_id)
+            .iter()
+            .filter_map(|point| point.watchers)
+            .map(u64::from)
+            .collect()
+    }
+
+    /// Writes the full history as CSV with one row per item and run.
+    pub fn write_csv(&self, path: &Path) -> color_eyre::Result<()> {
+        write_atomically(path, |writer| self.write_csv_to(writer))
+    }
+
+    fn write_csv_to(&self, writer: &mut dyn Write) -> color_eyre::Result<()> {
+        let mut wtr = csv::Writer::from_writer(writer);
+        wtr.write_record([
+            "item_id", "run_id", "recorded_at", "title", "price", "price_value", "watchers", "quantity_available",
+        ])?;
+        for item_id in self.item_ids() {
+            for point in &self.series[item_id] {
+                wtr.write_record([
+                    item_id,
+                    &point.run_id.to_string(),
+                    &point.recorded_at.to_rfc3339(),
+                    &point.title,
+                    &point.price,
+                    &parse_price(&point.price).map_or(String::new(), |price| format!("{:.2}", price)),
+                    &point.watchers.map_or(String::new(), |w| w.to_string()),
+                    &point.quantity.map_or(String::new(), |q| q.to_string()),
+                ])?;
+            }
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::Listing;
+    use crate::store::RunSummary;
+
+    #[test]
+    fn test_price_series_across_runs() {
+        let mut store = Store::open_in_memory().unwrap();
+        let summary = RunSummary {
+            seller: "seller123".to_string(),
+            started_at: Utc::now(),
+            finished_at: Utc::now(),
+            feedback_score: None,
+            items_sold: None,
+            follower_count: None,
+            partial: false,
+        };
+        for (price, watchers) in [("$12.00", Some(3)), ("$10.50", None), ("Make offer", Some(5)), ("$9.99", Some(8))] {
+            let listing = Listing {
+                title: "Sample Item".to_string(),
+                price: price.to_string(),
+                watchers,
+                item_id: Some("1234567890".to_string()),
+                ..Listing::default()
+            };
+            store.record_run(&summary, &[listing]).unwrap();
+        }
+
+        let history = PriceHistory::load(&store, "seller123").unwrap();
+        assert_eq!(history.points("1234567890").len(), 4);
+        assert_eq!(history.price_cents("1234567890"), vec![1200, 1050, 999]);
+        assert_eq!(history.watcher_counts("1234567890"), vec![3, 5, 8]);
+        assert!(history.points("missing").is_empty());
+    }
+}
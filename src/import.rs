@@ -0,0 +1,142 @@
+use crate::app::Listing;
+use crate::attributes::ListingFormat;
+use crate::export::ExportFormat;
+use color_eyre::eyre::eyre;
+use serde::Deserialize;
+use std::path::Path;
+
+/// One row of a CSV export. Every column is optional so partial exports can still be read.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct CsvRow {
+    title: String,
+    price: String,
+    shipping: Option<String>,
+    condition: Option<String>,
+    watchers: Option<u32>,
+    bids: Option<u32>,
+    sponsored: bool,
+    seller: Option<String>,
+    seller_feedback: Option<String>,
+    buy_it_now: bool,
+    accepts_offers: bool,
+    format: Option<ListingFormat>,
+    location: Option<String>,
+    quantity_available: Option<u32>,
+    is_new_listing: bool,
+    item_id: Option<String>,
+    url: Option<String>,
+    variation_id: Option<String>,
+    notes: String,
+    item_specifics: String,
+    description: Option<String>,
+}
+
+impl From<CsvRow> for Listing {
+    fn from(row: CsvRow) -> Self {
+        // Lists are exported as JSON arrays, older exports joined them with "; "
+        let split = |joined: String| -> Vec<String> {
+            if let Ok(parts) = serde_json::from_str(&joined) {
+                return parts;
+            }
+            joined
+                .split("; ")
+                .filter(|part| !part.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        Self {
+            title: row.title,
+            price: row.price,
+            shipping: row.shipping,
+            condition: row.condition,
+            watchers: row.watchers,
+            bids: row.bids,
+            sponsored: row.sponsored,
+            seller: row.seller,
+            seller_feedback: row.seller_feedback,
+            buy_it_now: row.buy_it_now,
+            accepts_offers: row.accepts_offers,
+            format: row.format,
+            location: row.location,
+            quantity_available: row.quantity_available,
+            is_new_listing: row.is_new_listing,
+            item_id: row.item_id,
+            url: row.url,
+            variation_id: row.variation_id,
+            notes: split(row.notes),
+            item_specifics: split(row.item_specifics),
+            description: row.description,
+        }
+    }
+}
+
+/// Reads listings back from a previously exported CSV, JSON or JSON Lines file, telling the
+/// format apart by the file extension.
+pub fn load_listings(path: &Path) -> color_eyre::Result<Vec<Listing>> {
+    match ExportFormat::from_path(path) {
+        Some(ExportFormat::Csv) | None => load_csv(path),
+        Some(ExportFormat::Json) => Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?),
+        Some(ExportFormat::Jsonl) => std::fs::read_to_string(path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| Ok(serde_json::from_str(line)?))
+            .collect(),
+        Some(format) => Err(eyre!("cannot read listings back from {} files", format.extension())),
+    }
+}
+
+fn load_csv(path: &Path) -> color_eyre::Result<Vec<Listing>> {
+    let mut reader = csv::Reader::from_path(path)?;
+    let mut listings = Vec::new();
+    for row in reader.deserialize::<CsvRow>() {
+        listings.push(row?.into());
+    }
+    Ok(listings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::{Column, export_listings};
+    use crate::marketplace::Marketplace;
+    use crate::store::RunSummary;
+    use chrono::Utc;
+
+    #[test]
+    fn test_round_trip_exports() {
+        let summary = RunSummary {
+            seller: "seller123".to_string(),
+            started_at: Utc::now(),
+            finished_at: Utc::now(),
+            feedback_score: None,
+            items_sold: None,
+            follower_count: None,
+            partial: false,
+        };
+        let listing = Listing {
+            title: "Sample Item".to_string(),
+            price: "$10.00".to_string(),
+            watchers: Some(3),
+            format: Some(ListingFormat::Auction),
+            item_id: Some("1234567890".to_string()),
+            notes: vec!["Free returns; 30 days".to_string()],
+            item_specifics: vec!["Brand: Acme".to_string()],
+            ..Listing::default()
+        };
+        let dir = std::env::temp_dir().join(format!("ebay_import_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        for name in ["listings.csv", "listings.json", "listings.jsonl"] {
+            let path = dir.join(name);
+            export_listings(&path, None, &Column::ALL, Marketplace::default(), &summary, std::slice::from_ref(&listing)).unwrap();
+            let loaded = load_listings(&path).unwrap();
+            assert_eq!(loaded.len(), 1, "{}", name);
+            assert_eq!(loaded[0].notes, listing.notes, "{}", name);
+            assert_eq!(loaded[0].item_specifics, listing.item_specifics, "{}", name);
+            assert_eq!(loaded[0].watchers, Some(3), "{}", name);
+            assert_eq!(loaded[0].format, Some(ListingFormat::Auction), "{}", name);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,155 @@
+use crate::marketplace::Marketplace;
+use std::fmt;
+
+/// Item page link broken down into the parts that identify the item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemUrl {
+    /// Marketplace host, e.g. `www.ebay.co.uk`.
+    pub host: String,
+    /// Numeric eBay item ID.
+    pub item_id: String,
+    /// Selected variation of a multi-variation listing, if the link has one.
+    pub variation_id: Option<String>,
+}
+
+impl ItemUrl {
+    /// Parses an item link in any of the shapes eBay uses:
+    ///
+    /// - `https://www.ebay.com/itm/1234567890`
+    /// - `https://www.ebay.co.uk/itm/levis-505-jeans/1234567890?var=987654`
+    /// - `/itm/1234567890?hash=item1f` (relative links on store pages)
+    /// - `https://m.ebay.de/itm/1234567890` (mobile pages)
+    /// - `https://cgi.ebay.com/ws/eBayISAPI.dll?ViewItem&item=1234567890`
+    /// - `https://www.ebay.com/p/123?iid=1234567890` (product pages pointing at an item)
+    ///
+    /// Relative links are taken to be on `page_host`. Returns `None` for links that are not item
+    /// pages, or not on eBay.
+    pub fn parse(href: &str, page_host: &str) -> Option<Self> {
+        let href = href.trim();
+        let href = href.split('#').next().unwrap_or(href);
+        let (location, query) = href.split_once('?').unwrap_or((href, ""));
+
+        let (host, path) = match location.split_once("://") {
+            Some((_, rest)) => {
+                let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+                (Some(canonical_host(authority)?), path)
+            }
+            None if location.starts_with("//") => {
+                let (authority, path) = location[2..].split_once('/').unwrap_or((&location[2..], ""));
+                (Some(canonical_host(authority)?), path)
+            }
+            None => (None, location.trim_start_matches('/')),
+        };
+
+        let params: Vec<(&str, &str)> = query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .collect();
+        let param = |name: &str| {
+            params
+                .iter()
+                .find(|(key, value)| *key == name && is_numeric(value))
+                .map(|(_, value)| value.to_string())
+        };
+
+        let segments: Vec<&str> = path.split('/').filter(|segment| !segment.is_empty()).collect();
+        let item_id = match segments.iter().position(|segment| *segment == "itm") {
+            // The ID is the last numeric segment, after an optional title slug
+            Some(index) => segments[index + 1..]
+                .iter()
+                .rev()
+                .find(|segment| is_numeric(segment))
+                .map(|segment| segment.to_string())
+                .or_else(|| param("item")),
+            None => param("item").or_else(|| param("iid")),
+        }?;
+
+        Some(Self {
+            host: host.unwrap_or_else(|| page_host.to_string()),
+            item_id,
+            variation_id: param("var"),
+        })
+    }
+}
+
+impl fmt::Display for ItemUrl {
+    /// Formats the canonical link of the item, without tracking parameters or title slug.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "https://{}/itm/{}", self.host, self.item_id)?;
+        if let Some(variation_id) = &self.variation_id {
+            write!(f, "?var={}", variation_id)?;
+        }
+        Ok(())
+    }
+}
+
+/// Maps any eBay host, e.g. `m.ebay.co.uk` or `cgi.ebay.com:443`, to the `www` host of its
+/// marketplace. Returns `None` for hosts outside the supported marketplaces.
+fn canonical_host(authority: &str) -> Option<String> {
+    let host = authority.rsplit('@').next()?.split(':').next()?.to_lowercase();
+    let domain = if let Some(domain) = host.strip_prefix("ebay.") {
+        domain
+    } else {
+        host.split_once(".ebay.")?.1
+    };
+    let host = format!("www.ebay.{}", domain);
+    Marketplace::ALL
+        .iter()
+        .find(|marketplace| marketplace.host() == host)
+        .map(|marketplace| marketplace.host().to_string())
+}
+
+fn is_numeric(text: &str) -> bool {
+    !text.is_empty() && text.chars().all(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_known_shapes() {
+        let cases = [
+            ("https://www.ebay.com/itm/1234567890", "https://www.ebay.com/itm/1234567890"),
+            (
+                "https://www.ebay.com/itm/1234567890?hash=item1f&_trkparms=abc#desc",
+                "https://www.ebay.com/itm/1234567890",
+            ),
+            (
+                "https://www.ebay.co.uk/itm/levis-505-32x30-jeans/1234567890?var=987654",
+                "https://www.ebay.co.uk/itm/1234567890?var=987654",
+            ),
+            ("/itm/1234567890", "https://www.ebay.com/itm/1234567890"),
+            ("https://m.ebay.de/itm/1234567890", "https://www.ebay.de/itm/1234567890"),
+            ("https://ebay.com.au/itm/1234567890", "https://www.ebay.com.au/itm/1234567890"),
+            (
+                "https://cgi.ebay.ca/ws/eBayISAPI.dll?ViewItem&item=1234567890",
+                "https://www.ebay.ca/itm/1234567890",
+            ),
+            ("https://www.ebay.com/p/4321?iid=1234567890", "https://www.ebay.com/itm/1234567890"),
+        ];
+        for (href, canonical) in cases {
+            let url = ItemUrl::parse(href, "www.ebay.com").unwrap_or_else(|| panic!("{} did not parse", href));
+            assert_eq!(url.item_id, "1234567890", "{}", href);
+            assert_eq!(url.to_string(), canonical, "{}", href);
+        }
+        assert_eq!(
+            ItemUrl::parse("https://www.ebay.co.uk/itm/1234567890?var=987654", "www.ebay.com").unwrap().variation_id.as_deref(),
+            Some("987654")
+        );
+        assert_eq!(
+            ItemUrl::parse("/itm/1234567890", "www.ebay.co.uk").unwrap().to_string(),
+            "https://www.ebay.co.uk/itm/1234567890"
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_other_links() {
+        assert_eq!(ItemUrl::parse("https://www.ebay.com/usr/thriftngo5", "www.ebay.com"), None);
+        assert_eq!(ItemUrl::parse("https://www.ebay.com/itm/levis-505-jeans", "www.ebay.com"), None);
+        assert_eq!(ItemUrl::parse("https://example.com/itm/1234567890", "www.ebay.com"), None);
+        assert_eq!(ItemUrl::parse("https://notebay.com/itm/1234567890", "www.ebay.com"), None);
+        assert_eq!(ItemUrl::parse("https://www.ebay.com.evil.example/itm/1234567890", "www.ebay.com"), None);
+        assert_eq!(ItemUrl::parse("//ebay.evil.example/itm/1234567890", "www.ebay.com"), None);
+    }
+}
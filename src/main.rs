@@ -1,28 +1,101 @@
 use crate::app::App;
+use crate::cli::{Cli, Command, SelectorsCommand};
+use crate::diff::ListingDiff;
+use crate::export::ExportFormat;
+use crate::history::PriceHistory;
+use crate::selectors::SelectorRegistry;
+use crate::store::Store;
+use clap::Parser;
 use log::{error, info};
 use std::io::IsTerminal;
 
 pub mod app;
+pub mod attributes;
+pub mod cache;
+pub mod captcha;
+pub mod checkpoint;
+pub mod cli;
+pub mod condition;
+pub mod coverage;
+pub mod diff;
 pub mod event;
+pub mod export;
+pub mod history;
+pub mod import;
+pub mod item_url;
+pub mod marketplace;
+pub mod parquet_export;
+pub mod pipeline;
+pub mod pool;
+pub mod rate_limit;
+pub mod selectors;
+pub mod store;
 pub mod ui;
 
 #[tokio::main]
 async fn main() -> color_eyre::Result<()> {
+    let cli = Cli::parse();
     let log_file = std::fs::File::create("app.log")?;
     env_logger::Builder::from_default_env()
         .filter_level(log::LevelFilter::Info)
         .target(env_logger::Target::Pipe(Box::new(log_file)))
         .init();
     info!("Starting up");
+    color_eyre::install()?;
+
+    let diff = match &cli.command {
+        Some(Command::Diff(args)) => {
+            let diff = ListingDiff::load(
+                args.old.as_ref(),
+                args.new.as_ref(),
+                &cli.scrape.database,
+                &cli.scrape.seller,
+            )?;
+            if let Some(report) = &args.report {
+                diff.write_report(report)?;
+                info!("Wrote diff report to {}", report.display());
+            }
+            Some(diff)
+        }
+        Some(Command::History(args)) => {
+            let store = Store::open(&cli.scrape.database)?;
+            let history = PriceHistory::load(&store, &cli.scrape.seller)?;
+            if ExportFormat::from_path(&args.output) == Some(ExportFormat::Parquet) {
+                parquet_export::write_price_snapshots(&args.output, &history)?;
+            } else {
+                history.write_csv(&args.output)?;
+            }
+            info!("Wrote price history of {} to {}", cli.scrape.seller, args.output.display());
+            return Ok(());
+        }
+        Some(Command::Selectors(SelectorsCommand::Check(args))) => {
+            let registry = SelectorRegistry::load(&cli.scrape.selectors)?;
+            let report = registry.check(&std::fs::read_to_string(&args.page)?);
+            print!("{}", report);
+            info!("Checked selectors against {}", args.page.display());
+            return Ok(());
+        }
+        Some(Command::View(_)) | None => None,
+    };
+    let viewed = match &cli.command {
+        Some(Command::View(args)) => Some((args.file.clone(), import::load_listings(&args.file)?)),
+        _ => None,
+    };
 
     if !std::io::stdout().is_terminal() {
         error!("Not running in a TTY. Exiting.");
         return Ok(());
     }
 
-    color_eyre::install()?;
+    let mut app = App::new(cli.scrape);
+    if let Some(diff) = diff {
+        app.show_diff(diff);
+    }
+    if let Some((path, listings)) = viewed {
+        app.view_listings(path, listings);
+    }
     let terminal = ratatui::init();
-    let result = App::new().run(terminal).await;
+    let result = app.run(terminal).await;
     ratatui::restore();
     if let Err(ref err) = result {
         error!("Error: {}", err);
@@ -0,0 +1,153 @@
+use clap::ValueEnum;
+
+/// eBay site a seller's store is scraped from. It decides the domain of every URL, the currency
+/// prices are expected in and the wording matched on the page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Marketplace {
+    /// ebay.com
+    #[default]
+    Us,
+    /// ebay.co.uk
+    Uk,
+    /// ebay.de
+    De,
+    /// ebay.com.au
+    Au,
+    /// ebay.ca
+    Ca,
+}
+
+/// Lowercase phrases a marketplace uses on store pages and listing cards.
+#[derive(Debug)]
+pub struct Vocabulary {
+    /// Title of the items sold counter on the store page, as written on the page.
+    pub items_sold: &'static str,
+    /// Word in the follower counter, e.g. `123 followers`.
+    pub followers: &'static str,
+    /// A Best Offer is accepted, e.g. `or Best Offer`.
+    pub best_offer: &'static [&'static str],
+    /// A fixed price Buy It Now listing.
+    pub buy_it_now: &'static [&'static str],
+    /// An auction listing, matched against the whole row.
+    pub auction: &'static [&'static str],
+    /// Shipping or delivery cost rows.
+    pub shipping: &'static [&'static str],
+    /// Starts of rows saying where the item is, e.g. `located in`.
+    pub location: &'static [&'static str],
+    /// Bid count rows, e.g. `5 bids`.
+    pub bids: &'static [&'static str],
+    /// Watcher count rows, e.g. `12 watchers`.
+    pub watchers: &'static [&'static str],
+    /// The "New Listing" badge, matched against the whole row.
+    pub new_listing: &'static [&'static str],
+    /// Labels of paid placements, matched against the whole row.
+    pub sponsored: &'static [&'static str],
+}
+
+const ENGLISH: Vocabulary = Vocabulary {
+    items_sold: "items sold",
+    followers: "follower",
+    best_offer: &["best offer"],
+    buy_it_now: &["buy it now"],
+    auction: &["auction"],
+    shipping: &["delivery", "shipping", "postage"],
+    location: &["located in", "from "],
+    bids: &["bid"],
+    watchers: &["watcher", "watching"],
+    new_listing: &["new listing"],
+    sponsored: &["sponsored", "promoted"],
+};
+
+const GERMAN: Vocabulary = Vocabulary {
+    items_sold: "verkaufte Artikel",
+    followers: "follower",
+    best_offer: &["preisvorschlag"],
+    buy_it_now: &["sofort-kaufen", "sofortkaufen"],
+    auction: &["auktion"],
+    shipping: &["versand", "lieferung"],
+    location: &["aus ", "standort"],
+    bids: &["gebot"],
+    watchers: &["beobachter"],
+    new_listing: &["neues angebot", "neu eingestellt"],
+    sponsored: &["gesponsert", "anzeige"],
+};
+
+impl Marketplace {
+    /// Every supported marketplace.
+    pub const ALL: [Marketplace; 5] = [Self::Us, Self::Uk, Self::De, Self::Au, Self::Ca];
+
+    /// Returns the host of the marketplace, e.g. `www.ebay.co.uk`.
+    pub fn host(self) -> &'static str {
+        match self {
+            Self::Us => "www.ebay.com",
+            Self::Uk => "www.ebay.co.uk",
+            Self::De => "www.ebay.de",
+            Self::Au => "www.ebay.com.au",
+            Self::Ca => "www.ebay.ca",
+        }
+    }
+
+    /// Returns the ISO code of the currency prices are listed in.
+    pub fn currency(self) -> &'static str {
+        match self {
+            Self::Us => "USD",
+            Self::Uk => "GBP",
+            Self::De => "EUR",
+            Self::Au => "AUD",
+            Self::Ca => "CAD",
+        }
+    }
+
+    /// Returns true if `price` is shown in the marketplace's currency, e.g. `£12.99` on ebay.co.uk.
+    pub fn matches_currency(self, price: &str) -> bool {
+        // "AU $" and "C $" prices also contain the plain dollar sign
+        if self == Self::Us && (price.contains("AU $") || price.contains("C $")) {
+            return false;
+        }
+        let symbols: &[&str] = match self {
+            Self::Us => &["$"],
+            Self::Uk => &["£"],
+            Self::De => &["EUR", "€"],
+            Self::Au => &["AU $"],
+            Self::Ca => &["C $"],
+        };
+        symbols.iter().any(|symbol| price.contains(symbol)) || price.contains(self.currency())
+    }
+
+    /// Returns the phrases matched on the marketplace's pages.
+    pub fn vocabulary(self) -> &'static Vocabulary {
+        match self {
+            Self::Us | Self::Uk | Self::Au | Self::Ca => &ENGLISH,
+            Self::De => &GERMAN,
+        }
+    }
+
+    /// Returns the URL of `seller`'s store page.
+    pub fn store_url(self, seller: &str) -> String {
+        format!("https://{}/usr/{}", self.host(), seller)
+    }
+
+    /// Returns the canonical URL of the item page of `item_id`.
+    pub fn item_url(self, item_id: &str) -> String {
+        format!("https://{}/itm/{}", self.host(), item_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_urls_and_currency() {
+        assert_eq!(Marketplace::Uk.store_url("thriftngo5"), "https://www.ebay.co.uk/usr/thriftngo5");
+        assert_eq!(Marketplace::Au.item_url("1234567890"), "https://www.ebay.com.au/itm/1234567890");
+        assert!(Marketplace::Uk.matches_currency("£12.99"));
+        assert!(!Marketplace::Uk.matches_currency("$12.99"));
+        assert!(Marketplace::De.matches_currency("EUR 12,50"));
+        assert!(!Marketplace::Au.matches_currency("$12.99"));
+        assert!(Marketplace::Au.matches_currency("AU $12.99"));
+        assert!(Marketplace::Us.matches_currency("$12.99"));
+        assert!(!Marketplace::Us.matches_currency("AU $12.99"));
+        assert!(!Marketplace::Us.matches_currency("C $12.99"));
+    }
+}
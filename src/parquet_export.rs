@@ -0,0 +1,262 @@
+use crate::app::{Listing, parse_price};
+use crate::export::{Exporter, SCHEMA_VERSION, write_atomically};
+use crate::history::PriceHistory;
+use crate::store::RunSummary;
+use arrow_array::builder::{ListBuilder, StringBuilder};
+use arrow_array::{
+    ArrayRef, BooleanArray, Float64Array, Int64Array, RecordBatch, StringArray, TimestampMicrosecondArray,
+    UInt32Array,
+};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::metadata::KeyValue;
+use parquet::file::properties::WriterProperties;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+
+fn timestamp_type() -> DataType {
+    DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()))
+}
+
+/// Schema of the listings table, one row per listing of a run.
+pub fn listings_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("scraped_at", timestamp_type(), false),
+        Field::new("run_seller", DataType::Utf8, false),
+        Field::new("position", DataType::UInt32, false),
+        Field::new("item_id", DataType::Utf8, true),
+        Field::new("title", DataType::Utf8, false),
+        Field::new("price", DataType::Utf8, false),
+        Field::new("price_value", DataType::Float64, true),
+        Field::new("shipping", DataType::Utf8, true),
+        Field::new("condition", DataType::Utf8, true),
+        Field::new("watchers", DataType::UInt32, true),
+        Field::new("seller", DataType::Utf8, true),
+        Field::new("seller_feedback", DataType::Utf8, true),
+        Field::new("buy_it_now", DataType::Boolean, false),
+        Field::new("accepts_offers", DataType::Boolean, false),
+        Field::new("location", DataType::Utf8, true),
+        Field::new("quantity_available", DataType::UInt32, true),
+        Field::new("is_new_listing", DataType::Boolean, false),
+        Field::new("url", DataType::Utf8, true),
+        Field::new(
+            "notes",
+            DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+            false,
+        ),
+        Field::new("description", DataType::Utf8, true),
+        Field::new("bids", DataType::UInt32, true),
+        Field::new("sponsored", DataType::Boolean, false),
+        Field::new("variation_id", DataType::Utf8, true),
+        Field::new("condition_category", DataType::Utf8, true),
+        Field::new("format", DataType::Utf8, true),
+    ]))
+}
+
+/// Schema of the item specifics table, one row per name/value pair of a listing.
+pub fn specifics_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("scraped_at", timestamp_type(), false),
+        Field::new("position", DataType::UInt32, false),
+        Field::new("item_id", DataType::Utf8, true),
+        Field::new("ordinal", DataType::UInt32, false),
+        Field::new("name", DataType::Utf8, false),
+        Field::new("value", DataType::Utf8, false),
+    ]))
+}
+
+/// Schema of the price snapshots table, one row per item and recorded run.
+pub fn price_snapshots_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("item_id", DataType::Utf8, false),
+        Field::new("run_id", DataType::Int64, false),
+        Field::new("recorded_at", timestamp_type(), false),
+        Field::new("title", DataType::Utf8, false),
+        Field::new("price", DataType::Utf8, false),
+        Field::new("price_value", DataType::Float64, true),
+        Field::new("watchers", DataType::UInt32, true),
+        Field::new("quantity_available", DataType::UInt32, true),
+    ]))
+}
+
+/// Builds the listings table of a run.
+pub fn listings_batch(summary: &RunSummary, listings: &[Listing]) -> color_eyre::Result<RecordBatch> {
+    let scraped_at = summary.finished_at.timestamp_micros();
+    let mut notes = ListBuilder::new(StringBuilder::new());
+    for listing in listings {
+        for note in &listing.notes {
+            notes.values().append_value(note);
+        }
+        notes.append(true);
+    }
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(TimestampMicrosecondArray::from(vec![scraped_at; listings.len()]).with_timezone("UTC")),
+        Arc::new(StringArray::from(vec![summary.seller.as_str(); listings.len()])),
+        Arc::new(UInt32Array::from_iter_values(0..listings.len() as u32)),
+        Arc::new(StringArray::from_iter(listings.iter().map(|l| l.item_id.as_deref()))),
+        Arc::new(StringArray::from_iter_values(listings.iter().map(|l| l.title.as_str()))),
+        Arc::new(StringArray::from_iter_values(listings.iter().map(|l| l.price.as_str()))),
+        Arc::new(Float64Array::from_iter(listings.iter().map(Listing::price_value))),
+        Arc::new(StringArray::from_iter(listings.iter().map(|l| l.shipping.as_deref()))),
+        Arc::new(StringArray::from_iter(listings.iter().map(|l| l.condition.as_deref()))),
+        Arc::new(UInt32Array::from_iter(listings.iter().map(|l| l.watchers))),
+        Arc::new(StringArray::from_iter(listings.iter().map(|l| l.seller.as_deref()))),
+        Arc::new(StringArray::from_iter(listings.iter().map(|l| l.seller_feedback.as_deref()))),
+        Arc::new(BooleanArray::from_iter(listings.iter().map(|l| Some(l.buy_it_now)))),
+        Arc::new(BooleanArray::from_iter(listings.iter().map(|l| Some(l.accepts_offers)))),
+        Arc::new(StringArray::from_iter(listings.iter().map(|l| l.location.as_deref()))),
+        Arc::new(UInt32Array::from_iter(listings.iter().map(|l| l.quantity_available))),
+        Arc::new(BooleanArray::from_iter(listings.iter().map(|l| Some(l.is_new_listing)))),
+        Arc::new(StringArray::from_iter(listings.iter().map(|l| l.url.as_deref()))),
+        Arc::new(notes.finish()),
+        Arc::new(StringArray::from_iter(listings.iter().map(|l| l.description.as_deref()))),
+        Arc::new(UInt32Array::from_iter(listings.iter().map(|l| l.bids))),
+        Arc::new(BooleanArray::from_iter(listings.iter().map(|l| Some(l.sponsored)))),
+        Arc::new(StringArray::from_iter(listings.iter().map(|l| l.variation_id.as_deref()))),
+        Arc::new(StringArray::from_iter(
+            listings.iter().map(|l| l.condition_category().map(|condition| condition.to_string())),
+        )),
+        Arc::new(StringArray::from_iter(listings.iter().map(|l| l.format.map(|format| format.as_str())))),
+    ];
+    Ok(RecordBatch::try_new(listings_schema(), columns)?)
+}
+
+/// Builds the item specifics table of a run. Specifics are split into name and value the same
+/// way the SQLite store does.
+pub fn specifics_batch(summary: &RunSummary, listings: &[Listing]) -> color_eyre::Result<RecordBatch> {
+    let mut positions = Vec::new();
+    let mut item_ids = Vec::new();
+    let mut ordinals = Vec::new();
+    let mut names = Vec::new();
+    let mut values = Vec::new();
+    for (position, listing) in listings.iter().enumerate() {
+        for (ordinal, specific) in listing.item_specifics.iter().enumerate() {
+            let (name, value) = specific.split_once(':').unwrap_or(("", specific));
+            positions.push(position as u32);
+            item_ids.push(listing.item_id.as_deref());
+            ordinals.push(ordinal as u32);
+            names.push(name.trim());
+            values.push(value.trim());
+        }
+    }
+
+    let scraped_at = summary.finished_at.timestamp_micros();
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(TimestampMicrosecondArray::from(vec![scraped_at; names.len()]).with_timezone("UTC")),
+        Arc::new(UInt32Array::from(positions)),
+        Arc::new(StringArray::from(item_ids)),
+        Arc::new(UInt32Array::from(ordinals)),
+        Arc::new(StringArray::from(names)),
+        Arc::new(StringArray::from(values)),
+    ];
+    Ok(RecordBatch::try_new(specifics_schema(), columns)?)
+}
+
+/// Builds the price snapshots table from the recorded history, ordered by item and run.
+pub fn price_snapshots_batch(history: &PriceHistory) -> color_eyre::Result<RecordBatch> {
+    let rows: Vec<_> = history
+        .item_ids()
+        .into_iter()
+        .flat_map(|item_id| history.points(item_id).iter().map(move |point| (item_id, point)))
+        .collect();
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|(item_id, _)| *item_id))),
+        Arc::new(Int64Array::from_iter_values(rows.iter().map(|(_, p)| p.run_id))),
+        Arc::new(
+            TimestampMicrosecondArray::from_iter_values(rows.iter().map(|(_, p)| p.recorded_at.timestamp_micros()))
+                .with_timezone("UTC"),
+        ),
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|(_, p)| p.title.as_str()))),
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|(_, p)| p.price.as_str()))),
+        Arc::new(Float64Array::from_iter(rows.iter().map(|(_, p)| parse_price(&p.price)))),
+        Arc::new(UInt32Array::from_iter(rows.iter().map(|(_, p)| p.watchers))),
+        Arc::new(UInt32Array::from_iter(rows.iter().map(|(_, p)| p.quantity))),
+    ];
+    Ok(RecordBatch::try_new(price_snapshots_schema(), columns)?)
+}
+
+/// Writes `batch` as a Snappy-compressed Parquet file to `writer`, tagged with the export
+/// schema version.
+fn write_batch<W: Write + Send>(batch: &RecordBatch, writer: W) -> color_eyre::Result<()> {
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .set_key_value_metadata(Some(vec![KeyValue::new(
+            "schema_version".to_string(),
+            SCHEMA_VERSION.to_string(),
+        )]))
+        .build();
+    let mut writer = ArrowWriter::try_new(writer, batch.schema(), Some(properties))?;
+    writer.write(batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+/// Writes the item specifics table of a run to `path`.
+pub fn write_specifics(path: &Path, summary: &RunSummary, listings: &[Listing]) -> color_eyre::Result<()> {
+    let batch = specifics_batch(summary, listings)?;
+    write_atomically(path, |writer| write_batch(&batch, writer))
+}
+
+/// Writes the price snapshots table to `path`.
+pub fn write_price_snapshots(path: &Path, history: &PriceHistory) -> color_eyre::Result<()> {
+    let batch = price_snapshots_batch(history)?;
+    write_atomically(path, |writer| write_batch(&batch, writer))
+}
+
+/// Parquet file with the listings table of a run. Item specifics go to a separate table, see
+/// [`write_specifics`].
+#[derive(Debug, Default)]
+pub struct ParquetExporter;
+
+impl Exporter for ParquetExporter {
+    fn export(&self, summary: &RunSummary, listings: &[Listing], writer: &mut dyn Write) -> color_eyre::Result<()> {
+        // The Arrow writer needs an owned `Send` sink, so the file is built in memory first
+        let mut buffer = Vec::new();
+        write_batch(&listings_batch(summary, listings)?, &mut buffer)?;
+        writer.write_all(&buffer)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_batches_match_schemas() {
+        let summary = RunSummary {
+            seller: "seller123".to_string(),
+            started_at: Utc::now(),
+            finished_at: Utc::now(),
+            feedback_score: None,
+            items_sold: None,
+            follower_count: None,
+            partial: false,
+        };
+        let listings = vec![
+            Listing {
+                title: "Sample Item".to_string(),
+                price: "$10.00".to_string(),
+                item_id: Some("1234567890".to_string()),
+                notes: vec!["Free returns".to_string()],
+                item_specifics: vec!["Brand: Acme".to_string(), "Color: Red".to_string()],
+                ..Listing::default()
+            },
+            Listing::default(),
+        ];
+
+        let batch = listings_batch(&summary, &listings).unwrap();
+        assert_eq!(batch.num_rows(), 2);
+        let specifics = specifics_batch(&summary, &listings).unwrap();
+        assert_eq!(specifics.num_rows(), 2);
+
+        let mut parquet = Vec::new();
+        ParquetExporter.export(&summary, &listings, &mut parquet).unwrap();
+        assert!(parquet.starts_with(b"PAR1"));
+    }
+}
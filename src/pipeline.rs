@@ -0,0 +1,299 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Named stage of a scrape run. Stages run one after another in the order of [`Stage::ALL`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Start geckodriver and open the WebDriver session.
+    Connect,
+    /// Scrape items sold, feedback and followers from the store page.
+    SellerStats,
+    /// Open the store's listings and parse every listing card.
+    ListingPages,
+    /// Visit every item page for item specifics and the description.
+    Enrich,
+    /// Save the results to the export file and the database.
+    Export,
+}
+
+impl Stage {
+    /// Every stage in the order they run.
+    pub const ALL: [Stage; 5] = [
+        Stage::Connect,
+        Stage::SellerStats,
+        Stage::ListingPages,
+        Stage::Enrich,
+        Stage::Export,
+    ];
+
+    /// Returns the stage that runs after this one, if any.
+    pub fn next(self) -> Option<Stage> {
+        let index = Self::ALL.iter().position(|&stage| stage == self)?;
+        Self::ALL.get(index + 1).copied()
+    }
+
+    /// Returns false for stages every later stage depends on.
+    pub fn is_skippable(self) -> bool {
+        self != Stage::Connect
+    }
+
+    /// Share of the progress bar taken by this stage, roughly matching how long it takes.
+    fn weight(self) -> f64 {
+        match self {
+            Stage::Connect => 0.1,
+            Stage::SellerStats => 0.1,
+            Stage::ListingPages => 0.15,
+            Stage::Enrich => 0.6,
+            Stage::Export => 0.05,
+        }
+    }
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Stage::Connect => "Connect",
+            Stage::SellerStats => "Seller stats",
+            Stage::ListingPages => "Listing pages",
+            Stage::Enrich => "Enrich",
+            Stage::Export => "Export",
+        })
+    }
+}
+
+/// Where a stage is at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StageStatus {
+    /// Not started yet.
+    Pending,
+    /// Currently running.
+    Running,
+    /// Finished successfully.
+    Done,
+    /// Passed over without running, with the reason.
+    Skipped(String),
+    /// Passed over because its result was restored from a checkpoint.
+    Restored,
+    /// Failed with the error; waits for the user to retry or skip it.
+    Failed(String),
+    /// Stopped early by the user, with a summary of the partial result.
+    Cancelled(String),
+}
+
+/// Result, error and timing of one stage.
+#[derive(Debug, Clone)]
+pub struct StageRecord {
+    /// The stage this record belongs to.
+    pub stage: Stage,
+    /// Current status.
+    pub status: StageStatus,
+    /// When the stage was last started.
+    pub started_at: Option<Instant>,
+    /// How long the last attempt took, once it is over.
+    pub duration: Option<Duration>,
+    /// Units of work done so far, e.g. enriched listings.
+    pub done: usize,
+    /// Units of work in total, or 0 while unknown.
+    pub total: usize,
+    /// Short summary of what the stage produced.
+    pub summary: String,
+}
+
+impl StageRecord {
+    fn new(stage: Stage) -> Self {
+        Self {
+            stage,
+            status: StageStatus::Pending,
+            started_at: None,
+            duration: None,
+            done: 0,
+            total: 0,
+            summary: String::new(),
+        }
+    }
+
+    /// Returns how long the stage took, or has been running for.
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.duration.or_else(|| self.started_at.map(|started_at| started_at.elapsed()))
+    }
+
+    /// Returns the completed share of the stage from 0.0 to 1.0.
+    pub fn fraction(&self) -> f64 {
+        match self.status {
+            StageStatus::Pending => 0.0,
+            StageStatus::Done | StageStatus::Skipped(_) | StageStatus::Restored | StageStatus::Cancelled(_) => 1.0,
+            StageStatus::Running | StageStatus::Failed(_) if self.total > 0 => {
+                (self.done as f64 / self.total as f64).min(1.0)
+            }
+            StageStatus::Running | StageStatus::Failed(_) => 0.0,
+        }
+    }
+
+    fn stop(&mut self, status: StageStatus) {
+        self.duration = self.started_at.map(|started_at| started_at.elapsed());
+        self.status = status;
+    }
+}
+
+/// State machine of a scrape run: one record per [`Stage`].
+///
+/// The app starts a stage, the stage's task reports progress and finally whether it finished or
+/// failed, and the app then moves on to the next stage. A failed stage stops the run until the
+/// user retries or skips it.
+#[derive(Debug, Clone)]
+pub struct Pipeline {
+    records: Vec<StageRecord>,
+}
+
+impl Default for Pipeline {
+    fn default() -> Self {
+        Self {
+            records: Stage::ALL.into_iter().map(StageRecord::new).collect(),
+        }
+    }
+}
+
+impl Pipeline {
+    /// Returns the records of every stage in run order.
+    pub fn records(&self) -> &[StageRecord] {
+        &self.records
+    }
+
+    /// Returns the record of `stage`.
+    pub fn record(&self, stage: Stage) -> &StageRecord {
+        self.records
+            .iter()
+            .find(|record| record.stage == stage)
+            .expect("every stage has a record")
+    }
+
+    fn record_mut(&mut self, stage: Stage) -> &mut StageRecord {
+        self.records
+            .iter_mut()
+            .find(|record| record.stage == stage)
+            .expect("every stage has a record")
+    }
+
+    /// Marks `stage` as running, discarding the results of an earlier attempt.
+    pub fn start(&mut self, stage: Stage) {
+        let record = self.record_mut(stage);
+        *record = StageRecord::new(stage);
+        record.status = StageStatus::Running;
+        record.started_at = Some(Instant::now());
+    }
+
+    /// Records that `done` out of `total` units of work of `stage` are complete.
+    pub fn advance(&mut self, stage: Stage, done: usize, total: usize) {
+        let record = self.record_mut(stage);
+        record.done = done;
+        record.total = total;
+    }
+
+    /// Marks `stage` as finished with a summary of its result.
+    pub fn finish(&mut self, stage: Stage, summary: String) {
+        let record = self.record_mut(stage);
+        record.summary = summary;
+        record.stop(StageStatus::Done);
+    }
+
+    /// Marks `stage` as failed with `error`.
+    pub fn fail(&mut self, stage: Stage, error: String) {
+        self.record_mut(stage).stop(StageStatus::Failed(error));
+    }
+
+    /// Marks `stage` as cancelled with a summary of what it got done.
+    pub fn cancel(&mut self, stage: Stage, summary: String) {
+        self.record_mut(stage).stop(StageStatus::Cancelled(summary));
+    }
+
+    /// Marks `stage` as skipped for `reason`.
+    pub fn skip(&mut self, stage: Stage, reason: &str) {
+        self.record_mut(stage).stop(StageStatus::Skipped(reason.to_string()));
+    }
+
+    /// Marks `stage` as restored from a checkpoint.
+    pub fn restore(&mut self, stage: Stage) {
+        self.record_mut(stage).stop(StageStatus::Restored);
+    }
+
+    /// Returns the stage that failed and waits for the user, if any.
+    pub fn failed(&self) -> Option<Stage> {
+        self.records
+            .iter()
+            .find(|record| matches!(record.status, StageStatus::Failed(_)))
+            .map(|record| record.stage)
+    }
+
+    /// Returns the stage currently running, if any.
+    pub fn running(&self) -> Option<Stage> {
+        self.records
+            .iter()
+            .find(|record| record.status == StageStatus::Running)
+            .map(|record| record.stage)
+    }
+
+    /// Returns true if the user cancelled a stage of this run.
+    pub fn was_cancelled(&self) -> bool {
+        self.records
+            .iter()
+            .any(|record| matches!(record.status, StageStatus::Cancelled(_)))
+    }
+
+    /// Returns true if `stage` was restored from a checkpoint instead of running in this run.
+    pub fn was_restored(&self, stage: Stage) -> bool {
+        self.record(stage).status == StageStatus::Restored
+    }
+
+    /// Returns true if a stage of this run was skipped. Stages restored from a checkpoint do
+    /// not count, their results are complete.
+    pub fn was_skipped(&self) -> bool {
+        self.records
+            .iter()
+            .any(|record| matches!(record.status, StageStatus::Skipped(_)))
+    }
+
+    /// Returns the overall progress from 0.0 to 1.0, based on the work each stage has done.
+    pub fn progress(&self) -> f64 {
+        self.records
+            .iter()
+            .map(|record| record.stage.weight() * record.fraction())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_follows_stages() {
+        let mut pipeline = Pipeline::default();
+        assert_eq!(pipeline.progress(), 0.0);
+
+        pipeline.start(Stage::Connect);
+        pipeline.finish(Stage::Connect, "session ready".to_string());
+        pipeline.restore(Stage::SellerStats);
+        pipeline.restore(Stage::ListingPages);
+        assert!(pipeline.was_restored(Stage::ListingPages));
+        assert!(!pipeline.was_skipped());
+        pipeline.start(Stage::Enrich);
+        pipeline.advance(Stage::Enrich, 5, 10);
+        assert!((pipeline.progress() - 0.65).abs() < 1e-9);
+
+        pipeline.fail(Stage::Enrich, "session lost".to_string());
+        assert_eq!(pipeline.failed(), Some(Stage::Enrich));
+        assert!(pipeline.record(Stage::Enrich).duration.is_some());
+
+        // Retrying starts the stage over
+        pipeline.start(Stage::Enrich);
+        assert_eq!(pipeline.failed(), None);
+        assert_eq!(pipeline.running(), Some(Stage::Enrich));
+        assert_eq!(pipeline.record(Stage::Enrich).fraction(), 0.0);
+
+        pipeline.cancel(Stage::Enrich, "3 listings enriched".to_string());
+        assert_eq!(pipeline.running(), None);
+        assert!(pipeline.was_cancelled());
+        assert_eq!(Stage::Enrich.next(), Some(Stage::Export));
+        assert_eq!(Stage::Export.next(), None);
+    }
+}
@@ -0,0 +1,217 @@
+use crate::app::{App, Listing};
+use crate::captcha::{CaptchaGate, CaptchaTimedOut};
+use crate::event::{AppEvent, Event};
+use crate::pipeline::Stage;
+use crate::rate_limit::RateLimiter;
+use crate::selectors::SelectorRegistry;
+use fantoccini::{Client, ClientBuilder};
+use log::{error, info};
+use std::collections::{BTreeSet, VecDeque};
+use std::process::{Child, Command};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::{Mutex, mpsc};
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+
+/// Port of the first extra geckodriver; the main session uses the default 4444.
+const BASE_PORT: u16 = 4445;
+
+/// How many times to try connecting to a freshly started geckodriver.
+const CONNECT_ATTEMPTS: u32 = 10;
+
+/// Extra WebDriver sessions used to enrich listings in parallel.
+///
+/// geckodriver only serves one session per process, so every session gets its own driver
+/// process on a dedicated port.
+#[derive(Debug)]
+pub struct SessionPool {
+    sessions: Vec<(Client, Child)>,
+}
+
+impl SessionPool {
+    /// Starts up to `count` extra sessions. Sessions that fail to start are logged and skipped.
+    pub async fn launch(count: usize) -> Self {
+        let mut sessions = Vec::new();
+        for offset in 0..count {
+            let port = BASE_PORT + offset as u16;
+            match Self::launch_session(port).await {
+                Ok(session) => sessions.push(session),
+                Err(e) => error!("Failed to start pooled session on port {}: {}", port, e),
+            }
+        }
+        info!("Session pool ready with {} extra sessions", sessions.len());
+        Self { sessions }
+    }
+
+    /// Starts a geckodriver on `port` and connects a minimized client to it.
+    async fn launch_session(port: u16) -> color_eyre::Result<(Client, Child)> {
+        let mut child = Command::new("./geckodriver")
+            .arg("--port")
+            .arg(port.to_string())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn()?;
+
+        let url = format!("http://localhost:{}", port);
+        let mut attempt = 0;
+        let client = loop {
+            attempt += 1;
+            match ClientBuilder::native().connect(&url).await {
+                Ok(client) => break client,
+                Err(e) if attempt >= CONNECT_ATTEMPTS => {
+                    let _ = child.kill();
+                    return Err(e.into());
+                }
+                Err(_) => tokio::time::sleep(tokio::time::Duration::from_millis(500)).await,
+            }
+        };
+        client.minimize_window().await?;
+        Ok((client, child))
+    }
+
+    /// Returns handles to every pooled session.
+    pub fn clients(&self) -> Vec<Client> {
+        self.sessions.iter().map(|(client, _)| client.clone()).collect()
+    }
+
+    /// Closes every session and stops its geckodriver.
+    pub async fn shutdown(self) {
+        for (client, mut child) in self.sessions {
+            if let Err(e) = client.close().await {
+                error!("Failed to close pooled session: {}", e);
+            }
+            let _ = child.kill();
+        }
+    }
+}
+
+/// Enriches listings with item details using one worker per client.
+///
+/// Workers pull listings from a shared queue, so a slow item never blocks the others, and every
+/// navigation still goes through the shared `rate_limiter`. An item page that turns out to be a
+/// challenge pauses every worker on `gate` and is retried once solved. Listings whose item ID is
+/// in `skip` are left as they are. Results are merged back in the original order; listings whose
+/// worker failed are returned unchanged. Once `cancel` fires, workers stop after dropping the item
+/// they are on, so the listings enriched so far are kept.
+///
+/// Returns the merged listings and how many of them were enriched.
+#[allow(clippy::too_many_arguments)]
+pub async fn enrich_listings(
+    clients: Vec<Client>,
+    listings: Vec<Listing>,
+    skip: &BTreeSet<String>,
+    rate_limiter: Arc<RateLimiter>,
+    selectors: Arc<SelectorRegistry>,
+    gate: Arc<CaptchaGate>,
+    sender: mpsc::UnboundedSender<Event>,
+    cancel: CancellationToken,
+) -> (Vec<Listing>, usize) {
+    let pending: VecDeque<_> = listings
+        .iter()
+        .cloned()
+        .enumerate()
+        .filter(|(_, listing)| {
+            listing
+                .item_id
+                .as_ref()
+                .is_none_or(|item_id| !skip.contains(item_id))
+        })
+        .collect();
+    let total = pending.len();
+    if total < listings.len() {
+        info!("Skipping {} already enriched listings", listings.len() - total);
+    }
+    let queue = Arc::new(Mutex::new(pending));
+    let completed = Arc::new(AtomicUsize::new(0));
+    let processed = Arc::new(AtomicUsize::new(0));
+
+    let mut workers = JoinSet::new();
+    for (worker, client) in clients.into_iter().enumerate() {
+        let queue = queue.clone();
+        let completed = completed.clone();
+        let processed = processed.clone();
+        let rate_limiter = rate_limiter.clone();
+        let selectors = selectors.clone();
+        let gate = gate.clone();
+        let sender = sender.clone();
+        let cancel = cancel.clone();
+
+        workers.spawn(async move {
+            let mut enriched = Vec::new();
+            while !cancel.is_cancelled() {
+                let Some((index, mut listing)) = queue.lock().await.pop_front() else {
+                    break;
+                };
+                let _ = sender.send(Event::App(AppEvent::WorkerProgress(
+                    worker,
+                    format!(
+                        "#{} {}",
+                        index + 1,
+                        listing.title.chars().take(30).collect::<String>()
+                    ),
+                )));
+
+                // Failed and URL-less items are returned unmarked, so a resumed run retries them
+                let ok = match listing.url.clone() {
+                    Some(item_url) => {
+                        let details = tokio::select! {
+                            _ = cancel.cancelled() => break,
+                            details = gate.guard(&client, || App::scrape_item_details(&client, &rate_limiter, &selectors, &item_url)) => details,
+                        };
+                        match details {
+                            Ok((item_specifics, description)) => {
+                                listing.item_specifics = item_specifics;
+                                listing.description = description;
+                                true
+                            }
+                            Err(e) if e.downcast_ref::<CaptchaTimedOut>().is_some() => {
+                                enriched.push((index, listing));
+                                continue;
+                            }
+                            Err(e) => {
+                                error!("Worker {} failed on {}: {}", worker + 1, item_url, e);
+                                false
+                            }
+                        }
+                    }
+                    None => false,
+                };
+
+                if ok {
+                    let _ = sender.send(Event::App(AppEvent::ListingEnriched(index, Box::new(listing.clone()))));
+                    completed.fetch_add(1, Ordering::SeqCst);
+                }
+                let done = processed.fetch_add(1, Ordering::SeqCst) + 1;
+                let _ = sender.send(Event::App(AppEvent::StageProgress(Stage::Enrich, done, total)));
+                let _ = sender.send(Event::App(AppEvent::SetStatus(format!(
+                    "🔍 Enriched {}/{} listings",
+                    completed.load(Ordering::SeqCst),
+                    total
+                ))));
+                enriched.push((index, listing));
+            }
+            let _ = sender.send(Event::App(AppEvent::WorkerProgress(
+                worker,
+                "idle".to_string(),
+            )));
+            enriched
+        });
+    }
+
+    let mut merged = listings;
+    while let Some(result) = workers.join_next().await {
+        match result {
+            Ok(enriched) => {
+                for (index, listing) in enriched {
+                    merged[index] = listing;
+                }
+            }
+            Err(e) => error!("Enrichment worker crashed: {}", e),
+        }
+    }
+    if cancel.is_cancelled() {
+        info!("Enrichment cancelled after {} listings", completed.load(Ordering::SeqCst));
+    }
+    (merged, completed.load(Ordering::SeqCst))
+}
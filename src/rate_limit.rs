@@ -0,0 +1,190 @@
+use chrono::{Local, NaiveTime, Timelike};
+use log::info;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Settings for the global navigation rate limiter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitConfig {
+    /// Sustained number of navigations allowed per minute.
+    pub requests_per_minute: u32,
+    /// Upper bound of the random delay added on top of every navigation.
+    pub jitter: Duration,
+    /// Number of navigations that may happen back to back before throttling kicks in.
+    pub burst: u32,
+    /// Local time window during which no navigation is allowed.
+    pub quiet_hours: Option<QuietHours>,
+}
+
+/// A daily window of local hours, e.g. `23-7`, that may wrap around midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuietHours {
+    /// First hour of the window (inclusive).
+    pub start: u32,
+    /// Hour at which the window ends (exclusive).
+    pub end: u32,
+}
+
+impl QuietHours {
+    /// Returns how long to wait from `now` until the window is over, or `None` outside of it.
+    pub fn remaining(&self, now: NaiveTime) -> Option<Duration> {
+        let hour = now.hour();
+        let inside = if self.start <= self.end {
+            hour >= self.start && hour < self.end
+        } else {
+            hour >= self.start || hour < self.end
+        };
+        if !inside {
+            return None;
+        }
+
+        let now_secs = now.num_seconds_from_midnight() as u64;
+        let end_secs = self.end as u64 * 3600;
+        let secs = if end_secs > now_secs {
+            end_secs - now_secs
+        } else {
+            24 * 3600 - now_secs + end_secs
+        };
+        Some(Duration::from_secs(secs))
+    }
+}
+
+impl FromStr for QuietHours {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s
+            .split_once('-')
+            .ok_or_else(|| format!("expected START-END hours, got '{}'", s))?;
+        let parse_hour = |value: &str| -> Result<u32, String> {
+            match value.trim().parse::<u32>() {
+                Ok(hour) if hour < 24 => Ok(hour),
+                _ => Err(format!("invalid hour '{}', expected 0-23", value)),
+            }
+        };
+        let quiet_hours = Self {
+            start: parse_hour(start)?,
+            end: parse_hour(end)?,
+        };
+        if quiet_hours.start == quiet_hours.end {
+            return Err("quiet hours start and end must differ".to_string());
+        }
+        Ok(quiet_hours)
+    }
+}
+
+/// Token bucket state shared by every caller of [`RateLimiter::acquire`].
+#[derive(Debug)]
+struct Bucket {
+    /// Available tokens; negative values are reservations made by waiting callers.
+    tokens: f64,
+    /// When tokens were last replenished.
+    last_refill: Instant,
+}
+
+/// Central politeness scheduler that every page navigation goes through.
+///
+/// Combines a token bucket (sustained rate plus burst cap), random jitter and optional quiet
+/// hours so that the scraper stays under eBay's bot thresholds regardless of how many tasks
+/// are navigating at once.
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    bucket: Mutex<Bucket>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter with a full bucket.
+    pub fn new(config: RateLimitConfig) -> Self {
+        let bucket = Bucket {
+            tokens: config.burst.max(1) as f64,
+            last_refill: Instant::now(),
+        };
+        Self {
+            config,
+            bucket: Mutex::new(bucket),
+        }
+    }
+
+    /// Waits until the next navigation is allowed.
+    pub async fn acquire(&self) {
+        if let Some(quiet_hours) = &self.config.quiet_hours
+            && let Some(wait) = quiet_hours.remaining(Local::now().time())
+        {
+            info!(
+                "Quiet hours {}-{} in effect, pausing for {}s",
+                quiet_hours.start,
+                quiet_hours.end,
+                wait.as_secs()
+            );
+            tokio::time::sleep(wait).await;
+        }
+
+        let wait = self.reserve(Instant::now()).await + self.jitter();
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Takes a token from the bucket and returns how long the caller must wait for it.
+    async fn reserve(&self, now: Instant) -> Duration {
+        let per_second = self.config.requests_per_minute.max(1) as f64 / 60.0;
+        let capacity = self.config.burst.max(1) as f64;
+
+        let mut bucket = self.bucket.lock().await;
+        let elapsed = now.saturating_duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * per_second).min(capacity);
+        bucket.last_refill = now;
+        bucket.tokens -= 1.0;
+
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / per_second)
+        }
+    }
+
+    /// Picks a random delay between zero and the configured jitter.
+    fn jitter(&self) -> Duration {
+        let max_ms = self.config.jitter.as_millis() as u64;
+        if max_ms == 0 {
+            Duration::ZERO
+        } else {
+            Duration::from_millis(fastrand::u64(0..=max_ms))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quiet_hours_window() {
+        let overnight: QuietHours = "23-7".parse().unwrap();
+        let at = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+
+        assert_eq!(overnight.remaining(at(12, 0)), None);
+        assert_eq!(overnight.remaining(at(6, 30)), Some(Duration::from_secs(30 * 60)));
+        assert_eq!(overnight.remaining(at(23, 0)), Some(Duration::from_secs(8 * 3600)));
+        assert!("7-7".parse::<QuietHours>().is_err());
+        assert!("25-3".parse::<QuietHours>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_burst_then_throttle() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            requests_per_minute: 60,
+            jitter: Duration::ZERO,
+            burst: 2,
+            quiet_hours: None,
+        });
+        let now = Instant::now();
+
+        assert_eq!(limiter.reserve(now).await, Duration::ZERO);
+        assert_eq!(limiter.reserve(now).await, Duration::ZERO);
+        assert_eq!(limiter.reserve(now).await, Duration::from_secs(1));
+        assert_eq!(limiter.reserve(now).await, Duration::from_secs(2));
+    }
+}
@@ -0,0 +1,308 @@
+use color_eyre::eyre::eyre;
+use log::info;
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+/// Newest selector file version this build understands.
+pub const SELECTORS_VERSION: u32 = 2;
+
+/// Definitions shipped with the binary, used when there is no selector file.
+const BUILT_IN: &str = include_str!("../selectors.json");
+
+/// Selectors of the fields of a listing card, each tried in order until one matches.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CardSelectors {
+    /// Listing title.
+    pub title: Vec<String>,
+    /// Price text.
+    pub price: Vec<String>,
+    /// Item condition.
+    pub condition: Vec<String>,
+    /// Attribute rows such as shipping, location, format, bids and watchers. Unlike the other
+    /// fields every match of every selector is used, and each row is classified by its text.
+    pub attribute_rows: Vec<String>,
+    /// Bare watcher counts of older cards that do not say "watchers".
+    pub watchers: Vec<String>,
+    /// Elements carrying a "Sponsored" or "Promoted" label.
+    #[serde(default)]
+    pub sponsored: Vec<String>,
+    /// Seller name and feedback.
+    pub seller: Vec<String>,
+    /// Elements searched for a "Best Offer" mention.
+    pub best_offer: Vec<String>,
+    /// Links to the item page.
+    pub link: Vec<String>,
+}
+
+impl CardSelectors {
+    /// Returns every field with its selectors, in card order.
+    pub fn fields(&self) -> [(&'static str, &[String]); 9] {
+        [
+            ("title", &self.title),
+            ("price", &self.price),
+            ("condition", &self.condition),
+            ("attribute_rows", &self.attribute_rows),
+            ("watchers", &self.watchers),
+            ("sponsored", &self.sponsored),
+            ("seller", &self.seller),
+            ("best_offer", &self.best_offer),
+            ("link", &self.link),
+        ]
+    }
+}
+
+/// Selector of one item specific on the item page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpecificSelector {
+    /// Name the specific is stored under, e.g. `Brand`.
+    pub name: String,
+    /// Selector of the specific's value.
+    pub selector: String,
+}
+
+/// Selectors used on an individual item page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemSelectors {
+    /// Item specifics read from the page, in order.
+    pub specifics: Vec<SpecificSelector>,
+    /// The description iframe.
+    pub description: String,
+}
+
+/// Every CSS selector the scraper relies on, loaded from a versioned JSON file so broken
+/// selectors can be fixed without recompiling.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectorRegistry {
+    /// Version of the definitions; files newer than [`SELECTORS_VERSION`] are rejected.
+    pub version: u32,
+    /// Selectors of the listing cards on a store page; the first one that matches is used.
+    pub cards: Vec<String>,
+    /// Selectors of the fields within a card.
+    pub card_fields: CardSelectors,
+    /// Selectors of the item page.
+    pub item: ItemSelectors,
+}
+
+impl Default for SelectorRegistry {
+    fn default() -> Self {
+        Self::parse(BUILT_IN).expect("built-in selectors are valid")
+    }
+}
+
+impl SelectorRegistry {
+    /// Loads the selector file at `path`, falling back to the built-in definitions when there is
+    /// none.
+    pub fn load(path: &Path) -> color_eyre::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let registry = Self::parse(&std::fs::read_to_string(path)?)
+            .map_err(|e| eyre!("invalid selector file {}: {}", path.display(), e))?;
+        info!("Loaded selectors version {} from {}", registry.version, path.display());
+        Ok(registry)
+    }
+
+    /// Parses selector definitions and makes sure every selector is valid CSS.
+    pub fn parse(json: &str) -> color_eyre::Result<Self> {
+        let registry: Self = serde_json::from_str(json)?;
+        if registry.version > SELECTORS_VERSION {
+            return Err(eyre!(
+                "version {} is newer than the supported version {}",
+                registry.version,
+                SELECTORS_VERSION
+            ));
+        }
+        let invalid: Vec<_> = registry
+            .all_selectors()
+            .filter(|selector| Selector::parse(selector).is_err())
+            .collect();
+        if !invalid.is_empty() {
+            return Err(eyre!("invalid selectors: {}", invalid.join(", ")));
+        }
+        Ok(registry)
+    }
+
+    fn all_selectors(&self) -> impl Iterator<Item = &str> {
+        self.cards
+            .iter()
+            .chain(self.card_fields.fields().into_iter().flat_map(|(_, selectors)| selectors))
+            .chain(self.item.specifics.iter().map(|specific| &specific.selector))
+            .chain(std::iter::once(&self.item.description))
+            .map(String::as_str)
+    }
+
+    /// Runs every selector against a saved page and reports what each one matched.
+    pub fn check(&self, html: &str) -> SelectorReport {
+        let document = Html::parse_document(html);
+        let cards: Vec<_> = self
+            .cards
+            .iter()
+            .map(|selector| (selector.clone(), select_all(&document.root_element(), selector).len()))
+            .collect();
+        let card_selector = cards.iter().find(|(_, count)| *count > 0).map(|(selector, _)| selector.clone());
+        let elements = card_selector
+            .as_deref()
+            .map(|selector| select_all(&document.root_element(), selector))
+            .unwrap_or_default();
+
+        let card_fields = self
+            .card_fields
+            .fields()
+            .into_iter()
+            .map(|(field, selectors)| {
+                let matches_with = |selector: &str, card: &ElementRef| {
+                    select_all(card, selector).iter().any(|element| {
+                        if field == "link" {
+                            element.value().attr("href").is_some()
+                        } else {
+                            !element.text().collect::<String>().trim().is_empty()
+                        }
+                    })
+                };
+                FieldReport {
+                    field: field.to_string(),
+                    selectors: selectors
+                        .iter()
+                        .map(|selector| {
+                            let count = elements.iter().filter(|card| matches_with(selector, card)).count();
+                            (selector.clone(), count)
+                        })
+                        .collect(),
+                    matched: elements
+                        .iter()
+                        .filter(|card| selectors.iter().any(|selector| matches_with(selector, card)))
+                        .count(),
+                    total: elements.len(),
+                }
+            })
+            .collect();
+
+        let item_fields = self
+            .item
+            .specifics
+            .iter()
+            .map(|specific| (specific.name.as_str(), &specific.selector))
+            .chain(std::iter::once(("Description", &self.item.description)))
+            .map(|(field, selector)| {
+                let count = select_all(&document.root_element(), selector).len();
+                FieldReport {
+                    field: field.to_string(),
+                    selectors: vec![(selector.clone(), count)],
+                    matched: usize::from(count > 0),
+                    total: 1,
+                }
+            })
+            .collect();
+
+        SelectorReport {
+            version: self.version,
+            cards,
+            card_selector,
+            card_fields,
+            item_fields,
+        }
+    }
+}
+
+/// Returns every element below `element` matching `selector`, or none if it is invalid.
+fn select_all<'a>(element: &ElementRef<'a>, selector: &str) -> Vec<ElementRef<'a>> {
+    Selector::parse(selector)
+        .map(|selector| element.select(&selector).collect())
+        .unwrap_or_default()
+}
+
+/// How one field's selector set fared against a page.
+#[derive(Debug, Clone)]
+pub struct FieldReport {
+    /// Name of the field.
+    pub field: String,
+    /// Every selector of the field with the number of cards (or elements) it matched.
+    pub selectors: Vec<(String, usize)>,
+    /// Number of cards where at least one of the selectors matched.
+    pub matched: usize,
+    /// Number of cards checked.
+    pub total: usize,
+}
+
+/// Result of running the selector registry against a saved page.
+#[derive(Debug, Clone)]
+pub struct SelectorReport {
+    /// Version of the checked selectors.
+    pub version: u32,
+    /// Every card selector with the number of cards it found.
+    pub cards: Vec<(String, usize)>,
+    /// The card selector the scraper would use, if any matched.
+    pub card_selector: Option<String>,
+    /// Card field results over the cards found by `card_selector`.
+    pub card_fields: Vec<FieldReport>,
+    /// Item page field results.
+    pub item_fields: Vec<FieldReport>,
+}
+
+impl fmt::Display for SelectorReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Selectors version {}", self.version)?;
+        writeln!(f)?;
+        writeln!(f, "Listing cards")?;
+        for (selector, count) in &self.cards {
+            let used = self.card_selector.as_ref() == Some(selector);
+            writeln!(f, "  {} {:>4}  {}", if used { "*" } else { " " }, count, selector)?;
+        }
+        for (title, fields) in [("Card fields", &self.card_fields), ("Item page", &self.item_fields)] {
+            writeln!(f)?;
+            writeln!(f, "{}", title)?;
+            for field in fields {
+                let status = if field.total == 0 || field.matched == 0 { "MISSING" } else { "ok" };
+                writeln!(f, "  {:<12} {:>4}/{:<4} {}", field.field, field.matched, field.total, status)?;
+                for (selector, count) in &field.selectors {
+                    writeln!(f, "      {:>4}  {}", count, selector)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_reports_matches() {
+        let registry = SelectorRegistry::default();
+        let html = r#"<html><body>
+            <div class="su-card-container">
+                <div class="s-card__title"><span class="su-styled-text">Levi's 505</span></div>
+                <div class="s-card__price">$25.00</div>
+                <a href="https://www.ebay.com/itm/123">link</a>
+            </div>
+            <div class="su-card-container">
+                <div class="s-card__price">$10.00</div>
+            </div>
+        </body></html>"#;
+
+        let report = registry.check(html);
+        assert_eq!(report.card_selector.as_deref(), Some("div.su-card-container"));
+        let field = |name: &str| report.card_fields.iter().find(|field| field.field == name).unwrap();
+        assert_eq!((field("title").matched, field("title").total), (1, 2));
+        assert_eq!(field("price").matched, 2);
+        assert_eq!(field("link").matched, 1);
+        assert!(report.item_fields.iter().all(|field| field.matched == 0));
+        assert!(report.to_string().contains("MISSING"));
+    }
+
+    #[test]
+    fn test_parse_rejects_newer_and_invalid() {
+        let mut registry = SelectorRegistry {
+            version: SELECTORS_VERSION + 1,
+            ..SelectorRegistry::default()
+        };
+        assert!(SelectorRegistry::parse(&serde_json::to_string(&registry).unwrap()).is_err());
+
+        registry.version = SELECTORS_VERSION;
+        registry.cards.push("div[".to_string());
+        assert!(SelectorRegistry::parse(&serde_json::to_string(&registry).unwrap()).is_err());
+    }
+}
@@ -0,0 +1,429 @@
+use crate::app::Listing;
+use crate::attributes::ListingFormat;
+use crate::history::PricePoint;
+use chrono::{DateTime, Utc};
+use color_eyre::eyre::eyre;
+use log::info;
+use rusqlite::{Connection, params};
+use std::path::Path;
+
+/// Schema of the run history database as first released. Every statement is idempotent so it
+/// runs on each open; later changes go in [`MIGRATIONS`].
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS sellers (
+    id INTEGER PRIMARY KEY,
+    username TEXT NOT NULL UNIQUE
+);
+CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY,
+    seller_id INTEGER NOT NULL REFERENCES sellers(id),
+    started_at TEXT NOT NULL,
+    finished_at TEXT NOT NULL,
+    feedback_score TEXT,
+    items_sold INTEGER,
+    follower_count INTEGER,
+    listing_count INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS listings (
+    item_id TEXT PRIMARY KEY,
+    seller_id INTEGER NOT NULL REFERENCES sellers(id),
+    url TEXT,
+    first_seen_run INTEGER NOT NULL REFERENCES runs(id),
+    last_seen_run INTEGER NOT NULL REFERENCES runs(id)
+);
+CREATE TABLE IF NOT EXISTS listing_snapshots (
+    run_id INTEGER NOT NULL REFERENCES runs(id),
+    position INTEGER NOT NULL,
+    item_id TEXT REFERENCES listings(item_id),
+    title TEXT NOT NULL,
+    price TEXT NOT NULL,
+    shipping TEXT,
+    condition TEXT,
+    watchers INTEGER,
+    seller TEXT,
+    seller_feedback TEXT,
+    buy_it_now INTEGER NOT NULL,
+    accepts_offers INTEGER NOT NULL,
+    location TEXT,
+    quantity_available INTEGER,
+    is_new_listing INTEGER NOT NULL,
+    url TEXT,
+    notes TEXT NOT NULL,
+    description TEXT,
+    PRIMARY KEY (run_id, position)
+);
+CREATE INDEX IF NOT EXISTS listing_snapshots_item ON listing_snapshots(item_id);
+CREATE TABLE IF NOT EXISTS item_specifics (
+    run_id INTEGER NOT NULL,
+    position INTEGER NOT NULL,
+    ordinal INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (run_id, position, ordinal),
+    FOREIGN KEY (run_id, position) REFERENCES listing_snapshots(run_id, position)
+);
+";
+
+/// Changes to [`SCHEMA`], oldest first. Migration `n` takes a database from `user_version` `n`
+/// to `n + 1`. Only ever append to this list, released migrations must not change.
+const MIGRATIONS: &[&str] = &[
+    // Bid count and selling format from the card's attribute rows
+    "ALTER TABLE listing_snapshots ADD COLUMN bids INTEGER;
+     ALTER TABLE listing_snapshots ADD COLUMN format TEXT;",
+    // Sponsored placements, so they can be told apart from organic listings
+    "ALTER TABLE listing_snapshots ADD COLUMN sponsored INTEGER NOT NULL DEFAULT 0;",
+    // Selected variation of multi-variation listings
+    "ALTER TABLE listing_snapshots ADD COLUMN variation_id TEXT;",
+    // Standard condition of the condition text, so snapshots can be grouped by it in SQL
+    "ALTER TABLE listing_snapshots ADD COLUMN condition_category TEXT;",
+    // Runs that did not scrape the whole store
+    "ALTER TABLE runs ADD COLUMN partial INTEGER NOT NULL DEFAULT 0;",
+];
+
+/// Seller-level results of a finished scrape run.
+#[derive(Debug, Clone)]
+pub struct RunSummary {
+    /// Seller whose store was scraped.
+    pub seller: String,
+    /// When the run started.
+    pub started_at: DateTime<Utc>,
+    /// When the run finished.
+    pub finished_at: DateTime<Utc>,
+    /// Seller feedback score.
+    pub feedback_score: Option<String>,
+    /// Seller items sold count.
+    pub items_sold: Option<u32>,
+    /// Seller follower count.
+    pub follower_count: Option<u32>,
+    /// Whether the run was cancelled, skipped a stage or was aborted by a CAPTCHA. Partial runs
+    /// are kept but never compared against or charted.
+    pub partial: bool,
+}
+
+/// Embedded SQLite store keeping the history of every scrape run.
+#[derive(Debug)]
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    /// Opens (or creates) the database at `path`.
+    pub fn open(path: &Path) -> color_eyre::Result<Self> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    /// Opens a throwaway in-memory database.
+    pub fn open_in_memory() -> color_eyre::Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(mut conn: Connection) -> color_eyre::Result<Self> {
+        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+        conn.execute_batch(SCHEMA)?;
+        Self::migrate(&mut conn)?;
+        Ok(Self { conn })
+    }
+
+    /// Applies the migrations the database has not seen yet, all in one transaction.
+    fn migrate(conn: &mut Connection) -> color_eyre::Result<()> {
+        let version: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        let version = version as usize;
+        if version > MIGRATIONS.len() {
+            return Err(eyre!(
+                "the database is at schema version {}, this build only knows up to {}",
+                version,
+                MIGRATIONS.len()
+            ));
+        }
+
+        let tx = conn.transaction()?;
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            tx.execute_batch(migration)?;
+            tx.pragma_update(None, "user_version", index as i64 + 1)?;
+            info!("Migrated the database to schema version {}", index + 1);
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Records a finished run with a snapshot of every listing and returns the new run ID.
+    pub fn record_run(&mut self, summary: &RunSummary, listings: &[Listing]) -> color_eyre::Result<i64> {
+        let tx = self.conn.transaction()?;
+
+        tx.execute(
+            "INSERT INTO sellers (username) VALUES (?1) ON CONFLICT(username) DO NOTHING",
+            params![summary.seller],
+        )?;
+        let seller_id: i64 = tx.query_row(
+            "SELECT id FROM sellers WHERE username = ?1",
+            params![summary.seller],
+            |row| row.get(0),
+        )?;
+
+        tx.execute(
+            "INSERT INTO runs (seller_id, started_at, finished_at, feedback_score, items_sold, follower_count, listing_count, partial)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                seller_id,
+                summary.started_at.to_rfc3339(),
+                summary.finished_at.to_rfc3339(),
+                summary.feedback_score,
+                summary.items_sold,
+                summary.follower_count,
+                listings.len() as i64,
+                summary.partial,
+            ],
+        )?;
+        let run_id = tx.last_insert_rowid();
+
+        for (position, listing) in listings.iter().enumerate() {
+            if let Some(item_id) = &listing.item_id {
+                tx.execute(
+                    "INSERT INTO listings (item_id, seller_id, url, first_seen_run, last_seen_run)
+                     VALUES (?1, ?2, ?3, ?4, ?4)
+                     ON CONFLICT(item_id) DO UPDATE SET url = excluded.url, last_seen_run = excluded.last_seen_run",
+                    params![item_id, seller_id, listing.url, run_id],
+                )?;
+            }
+
+            tx.execute(
+                "INSERT INTO listing_snapshots (run_id, position, item_id, title, price, shipping, condition,
+                     watchers, seller, seller_feedback, buy_it_now, accepts_offers, location,
+                     quantity_available, is_new_listing, url, notes, description, bids, format,
+                     sponsored, variation_id, condition_category)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20,
+                     ?21, ?22, ?23)",
+                params![
+                    run_id,
+                    position as i64,
+                    listing.item_id,
+                    listing.title,
+                    listing.price,
+                    listing.shipping,
+                    listing.condition,
+                    listing.watchers,
+                    listing.seller,
+                    listing.seller_feedback,
+                    listing.buy_it_now,
+                    listing.accepts_offers,
+                    listing.location,
+                    listing.quantity_available,
+                    listing.is_new_listing,
+                    listing.url,
+                    serde_json::to_string(&listing.notes)?,
+                    listing.description,
+                    listing.bids,
+                    listing.format.map(|format| format.as_str()),
+                    listing.sponsored,
+                    listing.variation_id,
+                    listing.condition_category().map(|condition| condition.to_string()),
+                ],
+            )?;
+
+            for (ordinal, specific) in listing.item_specifics.iter().enumerate() {
+                let (name, value) = specific.split_once(':').unwrap_or(("", specific));
+                tx.execute(
+                    "INSERT INTO item_specifics (run_id, position, ordinal, name, value) VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![run_id, position as i64, ordinal as i64, name.trim(), value.trim()],
+                )?;
+            }
+        }
+
+        tx.commit()?;
+        info!("Recorded run {} for {} with {} listings", run_id, summary.seller, listings.len());
+        Ok(run_id)
+    }
+
+    /// Returns the IDs of the `limit` most recent complete runs of `seller`, newest first.
+    pub fn recent_runs(&self, seller: &str, limit: usize) -> color_eyre::Result<Vec<i64>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT runs.id FROM runs JOIN sellers ON sellers.id = runs.seller_id
+             WHERE sellers.username = ?1 AND NOT runs.partial ORDER BY runs.id DESC LIMIT ?2",
+        )?;
+        let run_ids = stmt
+            .query_map(params![seller, limit as i64], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        Ok(run_ids)
+    }
+
+    /// Returns every snapshot of `seller`'s items from complete runs as `(item_id, point)`,
+    /// grouped by item and ordered from the oldest run to the newest.
+    pub fn item_snapshots(&self, seller: &str) -> color_eyre::Result<Vec<(String, PricePoint)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT s.item_id, s.run_id, runs.finished_at, s.title, s.price, s.watchers, s.quantity_available
+             FROM listing_snapshots s
+             JOIN runs ON runs.id = s.run_id
+             JOIN sellers ON sellers.id = runs.seller_id
+             WHERE sellers.username = ?1 AND s.item_id IS NOT NULL AND NOT runs.partial
+             ORDER BY s.item_id, s.run_id",
+        )?;
+        let snapshots = stmt
+            .query_map(params![seller], |row| {
+                Ok((
+                    row.get(0)?,
+                    PricePoint {
+                        run_id: row.get(1)?,
+                        recorded_at: row.get(2)?,
+                        title: row.get(3)?,
+                        price: row.get(4)?,
+                        watchers: row.get(5)?,
+                        quantity: row.get(6)?,
+                    },
+                ))
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok(snapshots)
+    }
+
+    /// Rebuilds the listings captured by run `run_id`, in their original order.
+    pub fn run_listings(&self, run_id: i64) -> color_eyre::Result<Vec<Listing>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT position, item_id, title, price, shipping, condition, watchers, seller, seller_feedback,
+                    buy_it_now, accepts_offers, location, quantity_available, is_new_listing, url, notes, description,
+                    bids, format, sponsored, variation_id
+             FROM listing_snapshots WHERE run_id = ?1 ORDER BY position",
+        )?;
+        let mut specifics_stmt = self.conn.prepare(
+            "SELECT name, value FROM item_specifics WHERE run_id = ?1 AND position = ?2 ORDER BY ordinal",
+        )?;
+
+        let rows = stmt.query_map(params![run_id], |row| {
+            let notes: String = row.get(15)?;
+            let format: Option<String> = row.get(18)?;
+            Ok((
+                row.get::<_, i64>(0)?,
+                notes,
+                Listing {
+                    item_id: row.get(1)?,
+                    title: row.get(2)?,
+                    price: row.get(3)?,
+                    shipping: row.get(4)?,
+                    condition: row.get(5)?,
+                    watchers: row.get(6)?,
+                    seller: row.get(7)?,
+                    seller_feedback: row.get(8)?,
+                    buy_it_now: row.get(9)?,
+                    accepts_offers: row.get(10)?,
+                    location: row.get(11)?,
+                    quantity_available: row.get(12)?,
+                    is_new_listing: row.get(13)?,
+                    url: row.get(14)?,
+                    description: row.get(16)?,
+                    bids: row.get(17)?,
+                    format: format.as_deref().and_then(ListingFormat::parse),
+                    sponsored: row.get(19)?,
+                    variation_id: row.get(20)?,
+                    ..Listing::default()
+                },
+            ))
+        })?;
+
+        let mut listings = Vec::new();
+        for row in rows {
+            let (position, notes, mut listing) = row?;
+            listing.notes = serde_json::from_str(&notes)?;
+            listing.item_specifics = specifics_stmt
+                .query_map(params![run_id, position], |row| {
+                    let name: String = row.get(0)?;
+                    let value: String = row.get(1)?;
+                    Ok(if name.is_empty() { value } else { format!("{}: {}", name, value) })
+                })?
+                .collect::<Result<_, _>>()?;
+            listings.push(listing);
+        }
+        Ok(listings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_reload_run() {
+        let mut store = Store::open_in_memory().unwrap();
+        let listing = Listing {
+            title: "Sample Item".to_string(),
+            price: "$10.00".to_string(),
+            item_id: Some("1234567890".to_string()),
+            notes: vec!["Free returns".to_string()],
+            item_specifics: vec!["Brand: Acme".to_string(), "Color: Red".to_string()],
+            bids: Some(4),
+            format: Some(ListingFormat::Auction),
+            sponsored: true,
+            variation_id: Some("987654".to_string()),
+            condition: Some("Pre-Owned".to_string()),
+            ..Listing::default()
+        };
+        let summary = RunSummary {
+            seller: "seller123".to_string(),
+            started_at: Utc::now(),
+            finished_at: Utc::now(),
+            feedback_score: None,
+            items_sold: Some(45),
+            follower_count: None,
+            partial: false,
+        };
+
+        let first_run = store.record_run(&summary, std::slice::from_ref(&listing)).unwrap();
+        let run_id = store.record_run(&summary, &[listing]).unwrap();
+
+        assert_eq!(store.recent_runs("seller123", 2).unwrap(), vec![run_id, first_run]);
+        let listings = store.run_listings(run_id).unwrap();
+        assert_eq!(listings.len(), 1);
+        assert_eq!(listings[0].title, "Sample Item");
+        assert_eq!(listings[0].notes, vec!["Free returns".to_string()]);
+        assert_eq!(listings[0].item_specifics, vec!["Brand: Acme".to_string(), "Color: Red".to_string()]);
+        assert_eq!((listings[0].bids, listings[0].format), (Some(4), Some(ListingFormat::Auction)));
+        assert!(listings[0].sponsored);
+        assert_eq!(listings[0].variation_id.as_deref(), Some("987654"));
+        let category: String = store
+            .conn
+            .query_row(
+                "SELECT condition_category FROM listing_snapshots WHERE run_id = ?1",
+                params![run_id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(category, "Used");
+
+        let snapshots = store.item_snapshots("seller123").unwrap();
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[0].0, "1234567890");
+        assert_eq!((snapshots[0].1.run_id, snapshots[1].1.run_id), (first_run, run_id));
+
+        // A cancelled run is kept, but is neither a diff baseline nor part of the history
+        let cancelled = RunSummary { partial: true, ..summary };
+        store.record_run(&cancelled, &[]).unwrap();
+        assert_eq!(store.recent_runs("seller123", 2).unwrap(), vec![run_id, first_run]);
+        assert_eq!(store.item_snapshots("seller123").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_migrates_old_database() {
+        let path = std::env::temp_dir().join(format!("ebay_store_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        // A database created before any migration existed
+        Connection::open(&path).unwrap().execute_batch(SCHEMA).unwrap();
+
+        let mut store = Store::open(&path).unwrap();
+        let version: i64 = store.conn.pragma_query_value(None, "user_version", |row| row.get(0)).unwrap();
+        assert_eq!(version as usize, MIGRATIONS.len());
+        let summary = RunSummary {
+            seller: "seller123".to_string(),
+            started_at: Utc::now(),
+            finished_at: Utc::now(),
+            feedback_score: None,
+            items_sold: None,
+            follower_count: None,
+            partial: false,
+        };
+        let run_id = store.record_run(&summary, &[Listing::default()]).unwrap();
+        assert_eq!(store.run_listings(run_id).unwrap().len(), 1);
+        drop(store);
+
+        // Reopening an up to date database changes nothing
+        Store::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,10 +1,15 @@
-use crate::app::{App, AppState, ScrollViewMode};
+use crate::app::{App, AppState, Listing, ScrollViewMode};
+use crate::captcha::ActiveChallenge;
+use crate::condition;
+use crate::coverage::FieldCoverage;
+use crate::diff::{ChangeKind, ListingDiff};
+use crate::pipeline::{StageRecord, StageStatus};
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Rect, Layout, Direction, Constraint},
     style::{Color, Stylize, Style},
     text::{Line, Span, Text},
-    widgets::{Block, BorderType, Paragraph, Widget, Table, Row, Cell},
+    widgets::{Block, BorderType, Clear, Paragraph, Sparkline, Widget, Table, Row, Cell},
 };
 
 impl Widget for &App {
@@ -13,11 +18,63 @@ impl Widget for &App {
         match self.state {
             AppState::Loading => self.render_loading(area, buf),
             AppState::Running => self.render_running(area, buf),
+            AppState::Diff => self.render_diff(area, buf),
+        }
+        if let Some(active) = &self.captcha {
+            self.render_captcha_modal(active, area, buf);
         }
     }
 }
 
 impl App {
+    fn render_captcha_modal(&self, active: &ActiveChallenge, area: Rect, buf: &mut Buffer) {
+        let width = area.width.min(70);
+        let height = area.height.min(11);
+        let modal_area = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+        Clear.render(modal_area, buf);
+
+        let block = Block::bordered()
+            .title(" 🚨 ACTION REQUIRED ")
+            .title_alignment(Alignment::Center)
+            .border_type(BorderType::Double)
+            .border_style(Style::default().fg(Color::Red).bold());
+        let inner_area = block.inner(modal_area);
+        block.render(modal_area, buf);
+
+        let elapsed = active.since.elapsed().as_secs();
+        let countdown = match self.options.captcha_timeout() {
+            Some(timeout) => format!(
+                "Aborting in {}s if not solved",
+                timeout.as_secs().saturating_sub(elapsed)
+            ),
+            None => format!("Waiting for {}s", elapsed),
+        };
+        let lines = vec![
+            Line::from(Span::styled(
+                format!("eBay served a {}", active.challenge.kind),
+                Style::default().fg(Color::Yellow).bold(),
+            )),
+            Line::from(Span::styled(
+                active.challenge.url.clone(),
+                Style::default().fg(Color::DarkGray),
+            )),
+            Line::from(""),
+            Line::from("Solve it in the Firefox window that was brought to the front."),
+            Line::from("Scraping continues automatically once the page is clear."),
+            Line::from(""),
+            Line::from(Span::styled(countdown, Style::default().fg(Color::Magenta).bold())),
+            Line::from(Span::styled("q/Esc: Quit", Style::default().fg(Color::DarkGray))),
+        ];
+        Paragraph::new(Text::from(lines))
+            .centered()
+            .render(inner_area, buf);
+    }
+
     fn render_loading(&self, area: Rect, buf: &mut Buffer) {
         let block = Block::bordered()
             .title("ebay")
@@ -27,12 +84,21 @@ impl App {
         let inner_area = block.inner(area);
         block.render(area, buf);
 
+        // Only show per-worker status when enriching with more than one session
+        let worker_lines = if self.worker_status.len() > 1 {
+            self.worker_status.len() as u16 + 1
+        } else {
+            0
+        };
+
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Min(0),
                 Constraint::Length(1), // For the paragraph
                 Constraint::Length(1), // For the gauge
+                Constraint::Length(self.pipeline.records().len() as u16 + 3), // For the stages
+                Constraint::Length(worker_lines), // For the worker status
                 Constraint::Min(0),
             ])
             .split(inner_area);
@@ -47,7 +113,7 @@ impl App {
             .centered();
 
         let num_dots = 20;
-        let filled_dots = (self.progress * num_dots as f64) as usize;
+        let filled_dots = (self.pipeline.progress() * num_dots as f64) as usize;
         let empty_dots = num_dots - filled_dots;
 
         let mut spans = Vec::new();
@@ -63,10 +129,259 @@ impl App {
 
         paragraph.render(chunks[1], buf);
         gauge_paragraph.render(chunks[2], buf);
+
+        let mut stage_lines = vec![Line::from("")];
+        stage_lines.extend(self.pipeline.records().iter().map(stage_line));
+        stage_lines.push(Line::from(""));
+        stage_lines.push(Line::from(Span::styled(
+            match self.pipeline.failed() {
+                Some(_) => "r: Retry stage | s: Skip stage | q/Esc: Quit",
+                None => "c: Cancel stage and keep results | q/Esc: Quit",
+            },
+            Style::default().fg(Color::DarkGray),
+        )));
+        Paragraph::new(stage_lines).centered().render(chunks[3], buf);
+
+        if worker_lines > 0 {
+            let mut lines = vec![Line::from("")];
+            for (worker, status) in self.worker_status.iter().enumerate() {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("Worker {}: ", worker + 1), Style::default().fg(Color::DarkGray)),
+                    Span::styled(status.as_str(), Style::default().fg(Color::Magenta)),
+                ]));
+            }
+            Paragraph::new(lines).centered().render(chunks[4], buf);
+        }
     }
 
     fn render_running(&self, area: Rect, buf: &mut Buffer) {
-        self.render_combined_scrollview(area, buf);
+        let selected = self.listings.get(self.selected_listing_index);
+        match selected {
+            Some(listing) if self.section_locked && self.scroll_view_mode == ScrollViewMode::Table => {
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Min(0), Constraint::Length(9)])
+                    .split(area);
+                self.render_combined_scrollview(chunks[0], buf);
+                self.render_listing_details(listing, chunks[1], buf);
+            }
+            _ => self.render_combined_scrollview(area, buf),
+        }
+    }
+
+    fn render_listing_details(&self, listing: &Listing, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .title("Selected Listing")
+            .title_alignment(Alignment::Center)
+            .border_type(BorderType::Rounded);
+        let inner_area = block.inner(area);
+        block.render(area, buf);
+
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(inner_area);
+
+        let item_id = listing.item_id.as_deref().unwrap_or("");
+        let points = self.price_history.points(item_id);
+        let details = vec![
+            Line::from(Span::styled(listing.title.clone(), Style::default().fg(Color::White).bold())),
+            Line::from(vec![
+                Span::styled("Price: ", Style::default().fg(Color::White)),
+                Span::styled(listing.price.clone(), Style::default().fg(Color::Green).bold()),
+            ]),
+            Line::from(vec![
+                Span::styled("Watchers: ", Style::default().fg(Color::White)),
+                Span::styled(
+                    listing.watchers.map_or("N/A".to_string(), |w| w.to_string()),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::styled("  Quantity: ", Style::default().fg(Color::White)),
+                Span::styled(
+                    listing.quantity_available.map_or("N/A".to_string(), |q| q.to_string()),
+                    Style::default().fg(Color::Blue),
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("Item ID: ", Style::default().fg(Color::White)),
+                Span::styled(item_id.to_string(), Style::default().fg(Color::DarkGray)),
+            ]),
+            Line::from(vec![
+                Span::styled("Recorded runs: ", Style::default().fg(Color::White)),
+                Span::styled(points.len().to_string(), Style::default().fg(Color::Cyan)),
+            ]),
+        ];
+        Paragraph::new(Text::from(details)).render(chunks[0], buf);
+
+        let charts = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(chunks[1]);
+
+        let prices = self.price_history.price_cents(item_id);
+        if let (Some(min), Some(max)) = (prices.iter().min(), prices.iter().max()) {
+            let title = format!(
+                "Price history: {:.2} - {:.2} {}",
+                *min as f64 / 100.0,
+                *max as f64 / 100.0,
+                self.options.marketplace.currency()
+            );
+            Sparkline::default()
+                .block(Block::new().title(title))
+                .data(&prices)
+                .max(*max)
+                .style(Style::default().fg(Color::Magenta))
+                .render(charts[0], buf);
+        } else {
+            Paragraph::new("No price history yet")
+                .fg(Color::DarkGray)
+                .centered()
+                .render(charts[0], buf);
+        }
+
+        let watchers = self.price_history.watcher_counts(item_id);
+        if let (Some(min), Some(max)) = (watchers.iter().min(), watchers.iter().max()) {
+            Sparkline::default()
+                .block(Block::new().title(format!("Watchers: {} - {}", min, max)))
+                .data(&watchers)
+                .max((*max).max(1))
+                .style(Style::default().fg(Color::Yellow))
+                .render(charts[1], buf);
+        } else {
+            Paragraph::new("No watcher history yet")
+                .fg(Color::DarkGray)
+                .centered()
+                .render(charts[1], buf);
+        }
+    }
+
+    fn render_diff(&self, area: Rect, buf: &mut Buffer) {
+        let main_block = Block::bordered()
+            .title("eBay Seller Dashboard - Changes")
+            .title_alignment(Alignment::Center)
+            .border_type(BorderType::Rounded);
+
+        let inner_area = main_block.inner(area);
+        main_block.render(area, buf);
+
+        let Some(diff) = &self.diff else {
+            Paragraph::new("No previous run to compare with")
+                .fg(Color::Magenta)
+                .bg(Color::Black)
+                .centered()
+                .render(inner_area, buf);
+            return;
+        };
+
+        let mut lines = Vec::new();
+        lines.push(Line::from(vec![
+            Span::styled("🔀 CHANGES", Style::default().fg(Color::Magenta).bold()),
+            Span::styled(
+                format!("  {} → {}", diff.old_label, diff.new_label),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled(format!("{} new", diff.added.len()), Style::default().fg(Color::Green).bold()),
+            Span::styled(" | ", Style::default().fg(Color::DarkGray)),
+            Span::styled(format!("{} ended", diff.removed.len()), Style::default().fg(Color::Red).bold()),
+            Span::styled(" | ", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!("{} repriced", diff.changes_of(ChangeKind::Price).count()),
+                Style::default().fg(Color::Yellow).bold(),
+            ),
+            Span::styled(" | ", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!("{} retitled", diff.changes_of(ChangeKind::Title).count()),
+                Style::default().fg(Color::Cyan).bold(),
+            ),
+            Span::styled(" | ", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!("{} quantity changes", diff.changes_of(ChangeKind::Quantity).count()),
+                Style::default().fg(Color::Blue).bold(),
+            ),
+        ]));
+        lines.push(Line::from(""));
+
+        if diff.is_empty() {
+            lines.push(Line::from("Nothing changed"));
+        } else {
+            Self::push_diff_sections(&mut lines, diff);
+        }
+
+        Paragraph::new(Text::from(lines))
+            .scroll((self.scroll_view_state.vertical_scroll as u16, 0))
+            .render(inner_area, buf);
+
+        let status_area = Rect {
+            x: area.x + 2,
+            y: area.y + area.height - 1,
+            width: area.width - 4,
+            height: 1,
+        };
+        let status_text = if self.listings.is_empty() {
+            "🔀 Changes | ↑/↓ j/k: Scroll | q/Esc: Quit"
+        } else {
+            "🔀 Changes | ↑/↓ j/k: Scroll | d: Back to dashboard | q/Esc: Quit"
+        };
+        Paragraph::new(status_text)
+            .fg(Color::Magenta)
+            .bg(Color::Black)
+            .alignment(Alignment::Center)
+            .render(status_area, buf);
+    }
+
+    fn push_diff_sections<'a>(lines: &mut Vec<Line<'a>>, diff: &'a ListingDiff) {
+        if !diff.added.is_empty() {
+            lines.push(Line::from(Span::styled("🆕 NEW LISTINGS", Style::default().fg(Color::Cyan).bold())));
+            for listing in &diff.added {
+                lines.push(Line::from(vec![
+                    Span::styled("+ ", Style::default().fg(Color::Green).bold()),
+                    Span::styled(format!("{:<12}", listing.price), Style::default().fg(Color::Green)),
+                    Span::styled(listing.title.as_str(), Style::default().fg(Color::White)),
+                ]));
+            }
+            lines.push(Line::from(""));
+        }
+
+        if !diff.removed.is_empty() {
+            lines.push(Line::from(Span::styled("🏁 ENDED LISTINGS", Style::default().fg(Color::Cyan).bold())));
+            for listing in &diff.removed {
+                lines.push(Line::from(vec![
+                    Span::styled("- ", Style::default().fg(Color::Red).bold()),
+                    Span::styled(format!("{:<12}", listing.price), Style::default().fg(Color::Red)),
+                    Span::styled(listing.title.as_str(), Style::default().fg(Color::DarkGray)),
+                ]));
+            }
+            lines.push(Line::from(""));
+        }
+
+        let sections = [
+            (ChangeKind::Price, "💲 PRICE CHANGES", Color::Yellow),
+            (ChangeKind::Title, "✏️ TITLE EDITS", Color::Cyan),
+            (ChangeKind::Quantity, "📦 QUANTITY CHANGES", Color::Blue),
+        ];
+        for (kind, heading, color) in sections {
+            let changes: Vec<_> = diff.changes_of(kind).collect();
+            if changes.is_empty() {
+                continue;
+            }
+            lines.push(Line::from(Span::styled(heading, Style::default().fg(Color::Cyan).bold())));
+            for change in changes {
+                lines.push(Line::from(vec![
+                    Span::styled("~ ", Style::default().fg(color).bold()),
+                    Span::styled(change.title.chars().take(50).collect::<String>(), Style::default().fg(Color::White)),
+                ]));
+                lines.push(Line::from(vec![
+                    Span::styled("    ", Style::default()),
+                    Span::styled(change.old_value.as_str(), Style::default().fg(Color::DarkGray)),
+                    Span::styled(" → ", Style::default().fg(Color::DarkGray)),
+                    Span::styled(change.new_value.as_str(), Style::default().fg(color).bold()),
+                ]));
+            }
+            lines.push(Line::from(""));
+        }
     }
 
     fn render_paragraph_view(&self, area: Rect, buf: &mut Buffer) {
@@ -308,7 +623,10 @@ impl App {
     fn render_combined_scrollview(&self, area: Rect, buf: &mut Buffer) {
         let main_block = Block::bordered()
             .title(format!(
-                "eBay Seller Dashboard{}", 
+                "eBay Seller Dashboard{}{}",
+                self.viewed_file
+                    .as_ref()
+                    .map_or(String::new(), |path| format!(" - {}", path.display())),
                 if self.section_locked { " - LOCKED" } else { "" }
             ))
             .title_alignment(Alignment::Center)
@@ -357,6 +675,59 @@ impl App {
         ]));
         
         combined_content.push(Line::from(""));
+
+        // Pipeline section, only for live scrapes
+        if self.viewed_file.is_none() {
+            combined_content.push(Line::from(vec![
+                Span::styled("⚙️ PIPELINE", Style::default().fg(Color::Cyan).bold())
+            ]));
+            combined_content.push(Line::from(""));
+            combined_content.extend(self.pipeline.records().iter().map(stage_line));
+            combined_content.push(Line::from(""));
+        }
+
+        // Condition breakdown, grouped by eBay's standard conditions
+        if !self.listings.is_empty() {
+            combined_content.push(Line::from(vec![
+                Span::styled("🏷️ CONDITIONS", Style::default().fg(Color::Cyan).bold())
+            ]));
+            combined_content.push(Line::from(""));
+            for stats in condition::summarize(&self.listings) {
+                let name = stats.condition.map_or("Unknown".to_string(), |condition| condition.to_string());
+                combined_content.push(Line::from(vec![
+                    Span::styled(format!("{:<24}", name), Style::default().fg(Color::Yellow).bold()),
+                    Span::styled(format!("{:>4} ", stats.count), Style::default().fg(Color::Green).bold()),
+                    Span::styled(
+                        stats.average_price.map_or(String::new(), |price| {
+                            format!("avg {:.2} {}", price, self.options.marketplace.currency())
+                        }),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ]));
+            }
+            combined_content.push(Line::from(""));
+        }
+
+        // Field coverage section, once the run has been exported
+        if let Some(coverage) = &self.coverage {
+            combined_content.push(Line::from(vec![
+                Span::styled("🧪 FIELD COVERAGE", Style::default().fg(Color::Cyan).bold())
+            ]));
+            combined_content.push(Line::from(""));
+            for drop in &self.coverage_drops {
+                combined_content.push(Line::from(vec![
+                    Span::styled(format!("⚠️  {}", drop), Style::default().fg(Color::Red).bold())
+                ]));
+            }
+            combined_content.extend(coverage.fields.iter().map(coverage_line));
+            if let Some(card_selector) = &coverage.card_selector {
+                combined_content.push(Line::from(vec![
+                    Span::styled("Cards found with ", Style::default().fg(Color::DarkGray)),
+                    Span::styled(card_selector.clone(), Style::default().fg(Color::White)),
+                ]));
+            }
+            combined_content.push(Line::from(""));
+        }
         combined_content.push(Line::from(""));
         
         // Table section header
@@ -390,7 +761,12 @@ impl App {
                     Style::default().fg(Color::White)
                 };
                 
-                let title_truncated = listing.title.chars().take(35).collect::<String>();
+                let title = if listing.sponsored {
+                    format!("[Ad] {}", listing.title)
+                } else {
+                    listing.title.clone()
+                };
+                let title_truncated = title.chars().take(35).collect::<String>();
                 let price = &listing.price;
                 let shipping = listing.shipping.as_deref().unwrap_or("N/A");
                 let condition = listing.condition.as_deref().unwrap_or("N/A");
@@ -456,6 +832,13 @@ impl App {
             ]));
         }
         
+        if self.diff.is_some() {
+            combined_content.push(Line::from(vec![
+                Span::styled("d: ", Style::default().fg(Color::Cyan)),
+                Span::styled("Show changes since previous run", Style::default().fg(Color::White))
+            ]));
+        }
+        
         combined_content.push(Line::from(vec![
             Span::styled("q/Esc: ", Style::default().fg(Color::Red)),
             Span::styled("Quit application", Style::default().fg(Color::White))
@@ -522,3 +905,55 @@ impl App {
         status_paragraph.render(status_area, buf);
     }
 }
+
+/// Renders one field's fill rate and the selector that supplied it, colored by how complete it is.
+fn coverage_line<'a>(coverage: &FieldCoverage) -> Line<'a> {
+    let rate = coverage.rate();
+    let color = if rate >= 0.9 {
+        Color::Green
+    } else if rate >= 0.5 {
+        Color::Yellow
+    } else {
+        Color::Red
+    };
+    let mut spans = vec![
+        Span::styled(format!("{:<16}", coverage.field), Style::default().fg(Color::Yellow).bold()),
+        Span::styled(format!("{:>4.0}% ", rate * 100.0), Style::default().fg(color).bold()),
+        Span::styled(
+            format!("{:>4}/{:<4} ", coverage.filled, coverage.total),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ];
+    if let Some(selector) = &coverage.selector {
+        spans.push(Span::styled(selector.clone(), Style::default().fg(Color::White)));
+    }
+    Line::from(spans)
+}
+
+/// Formats the status, timing and result of a pipeline stage as one line.
+fn stage_line<'a>(record: &StageRecord) -> Line<'a> {
+    let (icon, color, detail) = match &record.status {
+        StageStatus::Pending => ("·", Color::DarkGray, String::new()),
+        StageStatus::Running if record.total > 0 => ("▶", Color::Magenta, format!("{}/{}", record.done, record.total)),
+        StageStatus::Running => ("▶", Color::Magenta, String::new()),
+        StageStatus::Done => ("✔", Color::Green, record.summary.clone()),
+        StageStatus::Skipped(reason) => ("↷", Color::Yellow, format!("skipped: {}", reason)),
+        StageStatus::Restored => ("↺", Color::Cyan, "restored from checkpoint".to_string()),
+        StageStatus::Failed(error) => ("✘", Color::Red, error.clone()),
+        StageStatus::Cancelled(summary) => ("■", Color::Yellow, format!("cancelled: {}", summary)),
+    };
+    let mut spans = vec![
+        Span::styled(format!("{} ", icon), Style::default().fg(color)),
+        Span::styled(format!("{:<14}", record.stage.to_string()), Style::default().fg(color).bold()),
+    ];
+    if let Some(elapsed) = record.elapsed() {
+        spans.push(Span::styled(
+            format!("{:>7.1}s ", elapsed.as_secs_f64()),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    if !detail.is_empty() {
+        spans.push(Span::styled(detail, Style::default().fg(Color::White)));
+    }
+    Line::from(spans)
+}